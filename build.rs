@@ -0,0 +1,46 @@
+//! Generates a test for every example in `src/<day>/examples.txt`.
+
+use std::{env, fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src");
+
+    let mut days: Vec<_> = fs::read_dir("src")
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.join("examples.txt").is_file())
+        .collect();
+    days.sort();
+
+    let mut tests = String::new();
+    for dir in days {
+        let day = dir.file_name().unwrap().to_str().unwrap();
+        let path = fs::canonicalize(dir.join("examples.txt")).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+
+        for line in contents.lines() {
+            let name = match line.trim_end().strip_prefix("[example ") {
+                Some(name) => name.trim_end_matches(']').trim(),
+                None => continue,
+            };
+            let ident: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            tests += &format!(
+                "#[test]\nfn {}_example_{}() {{\n    crate::examples::test({:?}, {:?}, include_str!({:?}));\n}}\n\n",
+                day, ident, day, name, path
+            );
+        }
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).unwrap();
+}
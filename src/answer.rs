@@ -0,0 +1,265 @@
+use std::{
+    convert::{Infallible, TryFrom},
+    fmt,
+    str::FromStr,
+};
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// The answer to one part of a challenge.
+///
+/// Integers compare by value regardless of signedness, so `Answer::from(5u8)`
+/// equals `Answer::from(5i64)`.
+#[derive(Debug, Clone, Eq)]
+pub enum Answer {
+    Unsigned(u128),
+    Signed(i128),
+    Text(String),
+    /// Multi-line output, such as a rendered grid. Built from fewer than two
+    /// lines, it is [`Answer::Text`] instead, which is how it reads back.
+    Lines(Vec<String>),
+}
+
+impl Answer {
+    fn as_i128(&self) -> Option<i128> {
+        match *self {
+            Answer::Unsigned(n) => i128::try_from(n).ok(),
+            Answer::Signed(n) => Some(n),
+            _ => None,
+        }
+    }
+}
+
+fn equal(a: &Answer, b: &Answer) -> bool {
+    use Answer::*;
+    match (a, b) {
+        (Unsigned(a), Unsigned(b)) => a == b,
+        (Text(a), Text(b)) => a == b,
+        (Lines(a), Lines(b)) => a == b,
+        (Signed(_), _) | (_, Signed(_)) => match (a.as_i128(), b.as_i128()) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        equal(self, other)
+    }
+}
+
+/// Compares against an expected answer as written in a file or on the command line.
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        let Ok(other) = other.parse::<Answer>();
+        equal(self, &other)
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+macro_rules! impl_from_int {
+    ($variant:ident: $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n as _)
+                }
+            }
+
+            impl PartialEq<$t> for Answer {
+                fn eq(&self, &other: &$t) -> bool {
+                    equal(self, &Answer::from(other))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(Unsigned: u8, u16, u32, u64, u128, usize);
+impl_from_int!(Signed: i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(mut lines: Vec<String>) -> Self {
+        match lines.len() {
+            0 => Answer::Text(String::new()),
+            1 => Answer::Text(lines.remove(0)),
+            _ => Answer::Lines(lines),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Unsigned(n) => n.fmt(f),
+            Answer::Signed(n) => n.fmt(f),
+            Answer::Text(s) => s.fmt(f),
+            Answer::Lines(lines) => lines.join("\n").fmt(f),
+        }
+    }
+}
+
+/// Parses the written form of an answer, the inverse of `Display`.
+/// Anything that reads as an integer becomes one.
+impl FromStr for Answer {
+    type Err = Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_end_matches(&['\r', '\n'][..]);
+        if let Ok(n) = s.parse::<u128>() {
+            Ok(Answer::Unsigned(n))
+        } else if let Ok(n) = s.parse::<i128>() {
+            Ok(Answer::Signed(n))
+        } else if s.contains('\n') {
+            Ok(Answer::Lines(s.lines().map(str::to_string).collect()))
+        } else {
+            Ok(Answer::Text(s.to_string()))
+        }
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            // integers wider than 64 bits are written as strings, which not every
+            // consumer of JSON can represent faithfully as numbers
+            Answer::Unsigned(n) => match u64::try_from(*n) {
+                Ok(n) => serializer.serialize_u64(n),
+                Err(_) => serializer.collect_str(n),
+            },
+            Answer::Signed(n) => match i64::try_from(*n) {
+                Ok(n) => serializer.serialize_i64(n),
+                Err(_) => serializer.collect_str(n),
+            },
+            Answer::Text(s) => serializer.serialize_str(s),
+            Answer::Lines(lines) => {
+                let mut seq = serializer.serialize_seq(Some(lines.len()))?;
+                for line in lines {
+                    seq.serialize_element(line)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AnswerVisitor;
+
+        impl<'de> Visitor<'de> for AnswerVisitor {
+            type Value = Answer;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an integer, a string or a list of lines")
+            }
+            fn visit_u64<E: de::Error>(self, n: u64) -> Result<Answer, E> {
+                Ok(n.into())
+            }
+            fn visit_u128<E: de::Error>(self, n: u128) -> Result<Answer, E> {
+                Ok(n.into())
+            }
+            fn visit_i64<E: de::Error>(self, n: i64) -> Result<Answer, E> {
+                Ok(n.into())
+            }
+            fn visit_i128<E: de::Error>(self, n: i128) -> Result<Answer, E> {
+                Ok(n.into())
+            }
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Answer, E> {
+                let Ok(answer) = s.parse();
+                Ok(answer)
+            }
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Answer, A::Error> {
+                let mut lines = vec![];
+                while let Some(line) = seq.next_element()? {
+                    lines.push(line);
+                }
+                Ok(Answer::from(lines))
+            }
+        }
+
+        deserializer.deserialize_any(AnswerVisitor)
+    }
+}
+
+#[test]
+fn test_integer_equality() {
+    assert_eq!(Answer::from(5u8), Answer::from(5i64));
+    assert_eq!(Answer::from(-3isize), Answer::Signed(-3));
+    assert_ne!(Answer::from(u128::MAX), Answer::from(-1i128));
+    assert_ne!(Answer::from(5u32), Answer::from("5"));
+    assert_eq!(Answer::from(1202161486u128), 1202161486usize);
+}
+
+#[test]
+fn test_expected() {
+    assert_eq!(Answer::from(514579usize), "514579");
+    assert_eq!(Answer::from(-42isize), "-42\n");
+    assert_eq!(Answer::from(u128::MAX), u128::MAX.to_string().as_str());
+    assert_eq!(Answer::from("shiny gold"), "shiny gold");
+    assert_eq!(
+        Answer::from(vec!["#.".to_string(), ".#".to_string()]),
+        "#.\n.#"
+    );
+    assert_ne!(Answer::from(12usize), "13");
+}
+
+#[test]
+fn test_display_round_trip() {
+    let answers = vec![
+        Answer::from(0u8),
+        Answer::from(-7i32),
+        Answer::from(u128::MAX),
+        Answer::from("text"),
+        Answer::from(vec!["ab".to_string(), "cd".to_string()]),
+        Answer::from(vec!["ab".to_string()]),
+        Answer::from(Vec::<String>::new()),
+    ];
+    for answer in answers {
+        assert_eq!(answer.to_string().parse::<Answer>().unwrap(), answer);
+    }
+    assert_eq!(Answer::from(vec!["ab".to_string()]), Answer::from("ab"));
+}
+
+#[test]
+fn test_serde() {
+    let answers = vec![
+        (Answer::from(514579usize), "514579"),
+        (Answer::from(-7i32), "-7"),
+        (
+            Answer::from(u128::MAX),
+            "\"340282366920938463463374607431768211455\"",
+        ),
+        (Answer::from("text"), "\"text\""),
+        (
+            Answer::from(vec!["ab".to_string(), "cd".to_string()]),
+            "[\"ab\",\"cd\"]",
+        ),
+    ];
+    for (answer, json) in answers {
+        assert_eq!(serde_json::to_string(&answer).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Answer>(json).unwrap(), answer);
+    }
+}
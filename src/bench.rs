@@ -0,0 +1,311 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use serde::{Serialize, Serializer};
+
+use crate::{config::Overrides, error::Result, registry::DynChallenge, Part};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Options {
+    /// Untimed runs of each phase before measuring.
+    pub warmup: usize,
+    /// Timed runs of each phase.
+    pub iterations: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            warmup: 1,
+            iterations: 10,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Phase {
+    Parse,
+    Part(Part),
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+impl Serialize for Phase {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Summary of a set of timings. Durations are serialised as nanoseconds.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    pub samples: usize,
+    #[serde(rename = "mean_ns", serialize_with = "nanos")]
+    pub mean: Duration,
+    #[serde(rename = "median_ns", serialize_with = "nanos")]
+    pub median: Duration,
+    #[serde(rename = "stddev_ns", serialize_with = "nanos")]
+    pub stddev: Duration,
+    #[serde(rename = "min_ns", serialize_with = "nanos")]
+    pub min: Duration,
+    #[serde(rename = "max_ns", serialize_with = "nanos")]
+    pub max: Duration,
+}
+
+fn nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_nanos())
+}
+
+impl Stats {
+    /// Returns `None` when there are no samples.
+    pub fn new(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let total: Duration = samples.iter().sum();
+        let mean = total / n as u32;
+        // the middle two samples, which are the same one when `n` is odd
+        let median = (samples[(n - 1) / 2] + samples[n / 2]) / 2;
+        let variance = if n > 1 {
+            let mean = mean.as_secs_f64();
+            samples
+                .iter()
+                .map(|s| (s.as_secs_f64() - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Stats {
+            samples: n,
+            mean,
+            median,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+            min: samples[0],
+            max: samples[n - 1],
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Measurement {
+    pub day: &'static str,
+    pub phase: Phase,
+    /// `None` when the phase failed, with `error` saying why.
+    #[serde(flatten)]
+    pub stats: Option<Stats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Runs `f`, returning its output and how long it took.
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let output = f();
+    (output, start.elapsed())
+}
+
+/// Times parsing and both parts of `challenge`, each phase measured on its own.
+/// A part that fails is recorded with its error instead of timings, and the
+/// other part is still measured.
+pub fn bench(
+    challenge: &dyn DynChallenge,
+    input: &[u8],
+    overrides: &Overrides,
+    options: Options,
+) -> Result<Vec<Measurement>> {
+    let runs = options.warmup + options.iterations.max(1);
+
+    let mut samples = Vec::with_capacity(runs);
+    let mut parsed = None;
+    for _ in 0..runs {
+        let (result, elapsed) = time(|| challenge.parse_bytes(input, overrides));
+        samples.push(elapsed);
+        parsed = Some(result?);
+    }
+    let mut measurements = vec![Measurement {
+        day: challenge.name(),
+        phase: Phase::Parse,
+        stats: Stats::new(samples.split_off(options.warmup)),
+        error: None,
+    }];
+
+    let parsed = parsed.unwrap();
+    for &part in Part::ALL.iter() {
+        let mut samples = Vec::with_capacity(runs);
+        let mut error = None;
+        for _ in 0..runs {
+            let (result, elapsed) = time(|| parsed.part(part));
+            if let Err(err) = result {
+                error = Some(err.to_string());
+                break;
+            }
+            samples.push(elapsed);
+        }
+        let stats = match error {
+            Some(_) => None,
+            None => Stats::new(samples.split_off(options.warmup)),
+        };
+        measurements.push(Measurement {
+            day: challenge.name(),
+            phase: Phase::Part(part),
+            stats,
+            error,
+        });
+    }
+
+    Ok(measurements)
+}
+
+/// Formats a duration with three significant figures, e.g. `12.3µs`.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    let (value, unit) = if nanos < 1e3 {
+        (nanos, "ns")
+    } else if nanos < 1e6 {
+        (nanos / 1e3, "µs")
+    } else if nanos < 1e9 {
+        (nanos / 1e6, "ms")
+    } else {
+        (nanos / 1e9, "s")
+    };
+    let precision = if value < 10.0 {
+        2
+    } else if value < 100.0 {
+        1
+    } else {
+        0
+    };
+    format!("{:.*}{}", precision, value, unit)
+}
+
+pub fn table(measurements: &[Measurement]) -> String {
+    let mut table = format!(
+        "{:<8}{:<10}{:>10}{:>10}{:>10}{:>10}{:>10}\n",
+        "day", "phase", "mean", "median", "stddev", "min", "max"
+    );
+    for m in measurements {
+        table += &match (&m.stats, &m.error) {
+            (Some(stats), _) => format!(
+                "{:<8}{:<10}{:>10}{:>10}{:>10}{:>10}{:>10}\n",
+                m.day,
+                m.phase.to_string(),
+                format_duration(stats.mean),
+                format_duration(stats.median),
+                format_duration(stats.stddev),
+                format_duration(stats.min),
+                format_duration(stats.max),
+            ),
+            (None, error) => format!(
+                "{:<8}{:<10}error: {}\n",
+                m.day,
+                m.phase.to_string(),
+                error.as_deref().unwrap_or_default()
+            ),
+        };
+    }
+    table
+}
+
+#[derive(Serialize)]
+struct Json<'a> {
+    warmup: usize,
+    iterations: usize,
+    measurements: &'a [Measurement],
+}
+
+pub fn json(measurements: &[Measurement], options: Options) -> String {
+    serde_json::to_string_pretty(&Json {
+        warmup: options.warmup,
+        iterations: options.iterations,
+        measurements,
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_stats() {
+    let ms = Duration::from_millis;
+    let stats = Stats::new(vec![ms(4), ms(1), ms(3), ms(2)]).unwrap();
+    assert_eq!(stats.samples, 4);
+    assert_eq!(stats.mean, Duration::from_micros(2500));
+    assert_eq!(stats.median, Duration::from_micros(2500));
+    assert_eq!(stats.min, ms(1));
+    assert_eq!(stats.max, ms(4));
+    // sample standard deviation of 1, 2, 3, 4 is sqrt(5/3)
+    assert_eq!(stats.stddev.as_micros(), 1290);
+
+    let stats = Stats::new(vec![ms(7)]).unwrap();
+    assert_eq!(stats.median, ms(7));
+    assert_eq!(stats.stddev, Duration::from_secs(0));
+
+    assert!(Stats::new(vec![]).is_none());
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(Duration::from_nanos(512)), "512ns");
+    assert_eq!(format_duration(Duration::from_nanos(12_345)), "12.3µs");
+    assert_eq!(format_duration(Duration::from_micros(1_500)), "1.50ms");
+    assert_eq!(format_duration(Duration::from_secs(2)), "2.00s");
+}
+
+#[test]
+fn test_bench() {
+    let registry = crate::registry::challenges();
+    let day01 = registry.get("day01").unwrap();
+    let options = Options {
+        warmup: 2,
+        iterations: 3,
+    };
+
+    let measurements = bench(
+        day01,
+        b"1721\n979\n366\n299\n675\n1456",
+        &Overrides::default(),
+        options,
+    )
+    .unwrap();
+    let phases: Vec<_> = measurements.iter().map(|m| m.phase).collect();
+    assert_eq!(
+        phases,
+        vec![Phase::Parse, Phase::Part(Part::One), Phase::Part(Part::Two)]
+    );
+    assert!(measurements
+        .iter()
+        .all(|m| m.stats.map(|stats| stats.samples) == Some(3)));
+
+    let json: serde_json::Value = serde_json::from_str(&json(&measurements, options)).unwrap();
+    assert_eq!(json["iterations"], 3);
+    assert_eq!(json["measurements"][1]["day"], "day01");
+    assert_eq!(json["measurements"][1]["phase"], "part one");
+    assert!(json["measurements"][1]["median_ns"].is_u64());
+
+    // no pair sums to 2020, but parsing still works and both parts are listed
+    let measurements = bench(day01, b"1\n2", &Overrides::default(), options).unwrap();
+    assert!(measurements[0].stats.is_some());
+    assert!(measurements[1..]
+        .iter()
+        .all(|m| m.stats.is_none() && m.error.is_some()));
+    let table = table(&measurements);
+    assert!(table.contains("part one  error: "), "{}", table);
+    let failed: serde_json::Value =
+        serde_json::from_str(&self::json(&measurements, options)).unwrap();
+    assert!(failed["measurements"][1]["error"].is_string());
+    assert!(failed["measurements"][1].get("median_ns").is_none());
+
+    assert!(bench(day01, b"x", &Overrides::default(), options).is_err());
+}
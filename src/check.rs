@@ -0,0 +1,299 @@
+use std::{
+    fmt,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use nom::error::ErrorKind;
+
+use crate::{
+    answer::Answer,
+    config::Overrides,
+    error::{Error, Result},
+    read_file,
+    registry::DynChallenge,
+    Part,
+};
+
+/// The known answers for a day, stored next to its input as `expected.txt`:
+///
+/// ```text
+/// [part one]
+/// 514579
+/// [part two]
+/// 241861950
+/// ```
+///
+/// Either section may be left out. Answers can span several lines.
+#[derive(Debug, Default, PartialEq)]
+pub struct Expected {
+    part_one: Option<Answer>,
+    part_two: Option<Answer>,
+}
+
+impl Expected {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        match read_file(path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let mut expected = Expected::default();
+        for section in sections(contents)? {
+            expected.set(section.part()?, &section.lines);
+        }
+        Ok(expected)
+    }
+
+    pub fn set(&mut self, part: Part, lines: &[&str]) {
+        let answer = lines.join("\n");
+        let answer = answer.trim_matches('\n');
+        if answer.is_empty() {
+            return;
+        }
+        let Ok(answer) = answer.parse();
+        self.insert(part, answer);
+    }
+
+    pub fn insert(&mut self, part: Part, answer: Answer) {
+        match part {
+            Part::One => self.part_one = Some(answer),
+            Part::Two => self.part_two = Some(answer),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part_one.as_ref(),
+            Part::Two => self.part_two.as_ref(),
+        }
+    }
+}
+
+/// Writes the answers in the form [`Expected::parse`] reads.
+impl fmt::Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &part in Part::ALL.iter() {
+            if let Some(answer) = self.get(part) {
+                writeln!(f, "[part {}]\n{}", part, answer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `[header]` line and the lines that follow it, up to the next header.
+#[derive(Debug, PartialEq)]
+pub struct Section<'a> {
+    /// 1-based line number of the header.
+    pub line: usize,
+    pub header: &'a str,
+    pub lines: Vec<&'a str>,
+}
+
+impl Section<'_> {
+    pub fn error(&self) -> Error {
+        Error::Parse {
+            line: self.line,
+            column: 1,
+            kind: ErrorKind::Tag,
+            context: Some("section header"),
+            text: format!("[{}]", self.header),
+        }
+    }
+
+    /// The part named by a `[part one]` or `[part two]` header.
+    pub fn part(&self) -> Result<Part> {
+        match self.header {
+            "part one" => Ok(Part::One),
+            "part two" => Ok(Part::Two),
+            _ => Err(self.error()),
+        }
+    }
+}
+
+/// Splits `contents` into sections. Blank lines before the first header are
+/// ignored, but any other text there is an error.
+pub fn sections(contents: &str) -> Result<Vec<Section<'_>>> {
+    let mut sections: Vec<Section> = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with('[') && line.ends_with(']') {
+            sections.push(Section {
+                line: i + 1,
+                header: &line[1..line.len() - 1],
+                lines: vec![],
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.lines.push(line);
+        } else if !line.trim().is_empty() {
+            return Err(Error::Parse {
+                line: i + 1,
+                column: 1,
+                kind: ErrorKind::Tag,
+                context: Some("section header"),
+                text: line.to_string(),
+            });
+        }
+    }
+    Ok(sections)
+}
+
+#[derive(Debug)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: Answer,
+        actual: Answer,
+    },
+    /// There is no expected answer to compare with.
+    Missing(Answer),
+    Error(Error),
+}
+
+impl Status {
+    pub fn is_regression(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {}, got {})", expected, actual)
+            }
+            Status::Missing(actual) => write!(f, "missing (got {})", actual),
+            Status::Error(err) => write!(f, "ERROR ({})", err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub part: Part,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub parse: Duration,
+    /// `Err` when the input could not be parsed.
+    pub parts: Result<Vec<PartReport>>,
+}
+
+impl Report {
+    pub fn is_regression(&self) -> bool {
+        match &self.parts {
+            Ok(parts) => parts.iter().any(|p| p.status.is_regression()),
+            Err(_) => true,
+        }
+    }
+}
+
+pub fn check(
+    challenge: &dyn DynChallenge,
+    input: &[u8],
+    overrides: &Overrides,
+    expected: &Expected,
+) -> Report {
+    let start = Instant::now();
+    let parsed = challenge.parse_bytes(input, overrides);
+    let parse = start.elapsed();
+
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            return Report {
+                parse,
+                parts: Err(err),
+            }
+        }
+    };
+
+    let parts = Part::ALL
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = parsed.part(part);
+            let elapsed = start.elapsed();
+
+            let status = match (answer, expected.get(part)) {
+                (Err(err), _) => Status::Error(err),
+                (Ok(actual), None) => Status::Missing(actual),
+                (Ok(actual), Some(expected)) if actual == *expected => Status::Pass,
+                (Ok(actual), Some(expected)) => Status::Fail {
+                    expected: expected.clone(),
+                    actual,
+                },
+            };
+            PartReport {
+                part,
+                status,
+                elapsed,
+            }
+        })
+        .collect();
+
+    Report {
+        parse,
+        parts: Ok(parts),
+    }
+}
+
+#[test]
+fn test_parse_expected() {
+    let expected = Expected::parse("[part one]\n514579\n\n[part two]\n241861950\n").unwrap();
+    assert_eq!(expected.get(Part::One), Some(&Answer::from(514579usize)));
+    assert_eq!(expected.get(Part::Two), Some(&Answer::from(241861950usize)));
+
+    let expected = Expected::parse("[part two]\r\n#.#\r\n.#.\r\n").unwrap();
+    assert_eq!(expected.get(Part::One), None);
+    assert_eq!(expected.get(Part::Two).unwrap(), "#.#\n.#.");
+
+    assert_eq!(Expected::parse("").unwrap(), Expected::default());
+    assert!(matches!(
+        Expected::parse("\n514579\n"),
+        Err(Error::Parse {
+            line: 2,
+            column: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        Expected::parse("[part one]\n1\n[part three]\n3"),
+        Err(Error::Parse { line: 3, .. })
+    ));
+}
+
+#[test]
+fn test_check() {
+    let registry = crate::registry::challenges();
+    let day01 = registry.get("day01").unwrap();
+    let input = "1721\n979\n366\n299\n675\n1456";
+
+    let expected = Expected::parse("[part one]\n514579\n[part two]\n241861950").unwrap();
+    let report = check(day01, input.as_bytes(), &Overrides::default(), &expected);
+    assert!(!report.is_regression());
+    let parts = report.parts.unwrap();
+    assert!(matches!(parts[0].status, Status::Pass));
+    assert!(matches!(parts[1].status, Status::Pass));
+
+    let expected = Expected::parse("[part one]\n1").unwrap();
+    let report = check(day01, input.as_bytes(), &Overrides::default(), &expected);
+    assert!(report.is_regression());
+    let parts = report.parts.unwrap();
+    assert!(matches!(parts[0].status, Status::Fail { .. }));
+    assert!(matches!(parts[1].status, Status::Missing(_)));
+
+    let report = check(day01, b"nope", &Overrides::default(), &expected);
+    assert!(report.is_regression());
+    assert!(report.parts.is_err());
+}
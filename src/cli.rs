@@ -0,0 +1,1140 @@
+use std::{
+    convert::TryFrom,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    bench::{self, format_duration, time},
+    check::{self, Expected, Status},
+    config::{Config, Param},
+    error::{self, Error},
+    generate::{self, Rng},
+    input::{
+        self,
+        cache::{Cache, Origin},
+        fetch::{self, Client},
+        Resolver, Source,
+    },
+    parallel,
+    registry::{DynChallenge, Registry},
+    report::{self, Format, Record},
+    write_file, Part,
+};
+
+pub const SUCCESS: i32 = 0;
+pub const FAILURE: i32 = 1;
+pub const USAGE: i32 = 2;
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run {
+        days: Vec<String>,
+        part: Option<Part>,
+        input: Source,
+        inputs: Option<PathBuf>,
+        jobs: Option<usize>,
+        format: Format,
+        params: Vec<Param>,
+        config: Option<PathBuf>,
+    },
+    List,
+    Bench {
+        days: Vec<String>,
+        input: Source,
+        inputs: Option<PathBuf>,
+        options: bench::Options,
+        json: Option<PathBuf>,
+        params: Vec<Param>,
+        config: Option<PathBuf>,
+    },
+    Check {
+        days: Vec<String>,
+        inputs: Option<PathBuf>,
+        jobs: Option<usize>,
+        format: Format,
+        params: Vec<Param>,
+        config: Option<PathBuf>,
+    },
+    Report {
+        day: String,
+        input: Source,
+        inputs: Option<PathBuf>,
+        params: Vec<Param>,
+        config: Option<PathBuf>,
+    },
+    Fetch {
+        days: Vec<String>,
+        cache: Option<PathBuf>,
+    },
+    Import {
+        day: String,
+        from: Source,
+        name: String,
+        cache: Option<PathBuf>,
+    },
+    Install {
+        days: Vec<String>,
+        name: String,
+        inputs: Option<PathBuf>,
+        cache: Option<PathBuf>,
+    },
+    Cache {
+        cache: Option<PathBuf>,
+    },
+    Generate {
+        days: Vec<String>,
+        seed: Option<u64>,
+        size: usize,
+        inputs: Option<PathBuf>,
+    },
+    Help,
+}
+
+fn value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("{} requires a value", flag))
+}
+
+fn count<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<usize, String> {
+    let value = value(args, flag)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}", value, flag))
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let command = match args.next() {
+        None => {
+            return Ok(Command::Run {
+                days: vec![],
+                part: None,
+                input: Source::default(),
+                inputs: None,
+                jobs: None,
+                format: Format::Text,
+                params: vec![],
+                config: None,
+            })
+        }
+        Some(command) => command,
+    };
+
+    let mut days = vec![];
+    let mut flags = vec![];
+    let mut part = None;
+    let mut input = Source::default();
+    let mut inputs = None;
+    let mut from = None;
+    let mut cache = None;
+    let mut options = bench::Options::default();
+    let mut json = None;
+    let mut jobs = None;
+    let mut format = Format::Text;
+    let mut seed = None;
+    let mut size = generate::DEFAULT_SIZE;
+    let mut params: Vec<Param> = vec![];
+    let mut config = None;
+    while let Some(arg) = args.next() {
+        let flag = match arg.as_str() {
+            "--part" | "-p" => {
+                part = match value(&mut args, &arg)?.as_str() {
+                    "1" | "one" => Some(Part::One),
+                    "2" | "two" => Some(Part::Two),
+                    other => return Err(format!("invalid part '{}', expected 1 or 2", other)),
+                };
+                "--part"
+            }
+            "--input" | "-i" => {
+                input = Source::from_arg(&value(&mut args, &arg)?);
+                "--input"
+            }
+            "--inputs" => {
+                inputs = Some(PathBuf::from(value(&mut args, &arg)?));
+                "--inputs"
+            }
+            "--from" => {
+                from = match value(&mut args, &arg)?.as_str() {
+                    "-" => Some(Source::Stdin),
+                    path => Some(Source::Path(PathBuf::from(path))),
+                };
+                "--from"
+            }
+            "--cache" => {
+                cache = Some(PathBuf::from(value(&mut args, &arg)?));
+                "--cache"
+            }
+            "--iterations" | "-n" => {
+                options.iterations = count(&mut args, &arg)?;
+                if options.iterations == 0 {
+                    return Err("--iterations must be at least 1".to_string());
+                }
+                "--iterations"
+            }
+            "--warmup" | "-w" => {
+                options.warmup = count(&mut args, &arg)?;
+                "--warmup"
+            }
+            "--jobs" | "-j" => {
+                let n = count(&mut args, &arg)?;
+                if n == 0 {
+                    return Err("--jobs must be at least 1".to_string());
+                }
+                jobs = Some(n);
+                "--jobs"
+            }
+            "--format" | "-f" => {
+                format = value(&mut args, &arg)?.parse()?;
+                "--format"
+            }
+            "--json" => {
+                json = Some(PathBuf::from(value(&mut args, &arg)?));
+                "--json"
+            }
+            "--seed" => {
+                let value = value(&mut args, &arg)?;
+                seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid value '{}' for {}", value, arg))?,
+                );
+                "--seed"
+            }
+            "--size" => {
+                size = count(&mut args, &arg)?;
+                if size == 0 {
+                    return Err("--size must be at least 1".to_string());
+                }
+                "--size"
+            }
+            "--param" => {
+                params.push(value(&mut args, &arg)?.parse()?);
+                "--param"
+            }
+            "--config" => {
+                config = Some(PathBuf::from(value(&mut args, &arg)?));
+                "--config"
+            }
+            "--help" | "-h" => return Ok(Command::Help),
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            _ => {
+                days.push(arg);
+                continue;
+            }
+        };
+        flags.push(flag);
+    }
+
+    if !input.is_named() && days.len() != 1 {
+        return Err("--input with a file or stdin needs exactly one day".to_string());
+    }
+    if params.iter().any(|p| p.day.is_none()) && days.len() != 1 {
+        return Err("--param without a day needs exactly one day".to_string());
+    }
+
+    let (parsed, supported): (_, &[&str]) = match command.as_str() {
+        "run" => (
+            Command::Run {
+                days,
+                part,
+                input,
+                inputs,
+                jobs,
+                format,
+                params,
+                config,
+            },
+            &[
+                "--part", "--input", "--inputs", "--jobs", "--format", "--param", "--config",
+            ],
+        ),
+        "bench" => (
+            Command::Bench {
+                days,
+                input,
+                inputs,
+                options,
+                json,
+                params,
+                config,
+            },
+            &[
+                "--input",
+                "--inputs",
+                "--iterations",
+                "--warmup",
+                "--json",
+                "--param",
+                "--config",
+            ],
+        ),
+        "check" => (
+            Command::Check {
+                days,
+                inputs,
+                jobs,
+                format,
+                params,
+                config,
+            },
+            &["--inputs", "--jobs", "--format", "--param", "--config"],
+        ),
+        "report" => {
+            let day = match <[String; 1]>::try_from(days) {
+                Ok([day]) => day,
+                Err(_) => return Err("report needs exactly one day".to_string()),
+            };
+            (
+                Command::Report {
+                    day,
+                    input,
+                    inputs,
+                    params,
+                    config,
+                },
+                &["--input", "--inputs", "--param", "--config"],
+            )
+        }
+        "fetch" => (Command::Fetch { days, cache }, &["--cache"]),
+        "import" => {
+            let day = match <[String; 1]>::try_from(days) {
+                Ok([day]) => day,
+                Err(_) => return Err("import needs exactly one day".to_string()),
+            };
+            let from = from.ok_or("import needs --from PATH|-")?;
+            let Source::Named(name) = input else {
+                return Err("--input must be a name for 'import'".to_string());
+            };
+            (
+                Command::Import {
+                    day,
+                    from,
+                    name,
+                    cache,
+                },
+                &["--from", "--input", "--cache"],
+            )
+        }
+        "install" => {
+            let Source::Named(name) = input else {
+                return Err("--input must be a name for 'install'".to_string());
+            };
+            (
+                Command::Install {
+                    days,
+                    name,
+                    inputs,
+                    cache,
+                },
+                &["--input", "--inputs", "--cache"],
+            )
+        }
+        "generate" => {
+            if inputs.is_none() && days.len() != 1 {
+                return Err("generate needs exactly one day unless --inputs is given".to_string());
+            }
+            (
+                Command::Generate {
+                    days,
+                    seed,
+                    size,
+                    inputs,
+                },
+                &["--seed", "--size", "--inputs"],
+            )
+        }
+        "cache" if days.is_empty() => (Command::Cache { cache }, &["--cache"]),
+        "list" if days.is_empty() => (Command::List, &[]),
+        "help" | "--help" | "-h" => (Command::Help, &[]),
+        "list" | "cache" => return Err(format!("{} does not take any days", command)),
+        _ => return Err(format!("unknown command '{}'", command)),
+    };
+    if let Some(flag) = flags.iter().find(|flag| !supported.contains(flag)) {
+        return Err(format!("{} is not supported by '{}'", flag, command));
+    }
+    Ok(parsed)
+}
+
+fn usage(registry: &Registry) -> String {
+    let names: Vec<&str> = registry.iter().map(|c| c.name()).collect();
+    format!(
+        "Usage: adventofcode2020 [COMMAND]
+
+Commands:
+    run [DAY...] [--part 1|2] [--input INPUT]   Solve the given days (default: all)
+        [--jobs N] [--format FORMAT]            Solve up to N days at once (default: one per CPU)
+    list                                        List the available days
+    bench [DAY...] [--input INPUT]              Time parsing and each part of the given days
+          [--iterations N] [--warmup N]         (default: 10 timed runs after 1 warm-up run)
+          [--json PATH]                         Also write the timings to PATH as JSON
+    check [DAY...] [--jobs N] [--format FORMAT] Compare answers against <day>/expected.txt
+    report DAY [--input INPUT]                  Print what DAY found in its input as JSON, for
+                                                days that say more than their answers
+    fetch [DAY...]                              Download inputs into the cache
+    import DAY --from PATH|- [--input NAME]     Copy an input into the cache
+    install [DAY...] [--input NAME]             Write cached inputs to <dir>/<day>/<name>.txt
+    cache                                       List the cached inputs and where they came from
+    generate DAY... [--seed N] [--size N]       Print a random input for DAY, or with --inputs write
+             [--inputs DIR]                     <dir>/<day>/input.txt and expected.txt for each DAY
+    help                                        Print this message
+
+Options:
+    --input NAME|PATH|-     Read the input named NAME (default: input), the file PATH or stdin.
+                            A file or stdin needs exactly one day.
+    --inputs DIR            Search DIR for inputs before ${} and the crate's src directory.
+                            A named input is read from <dir>/<day>/<name>.txt.
+                            install writes to DIR instead of the crate's src directory.
+    --format FORMAT         Write results as text (default), json (one object per line), csv,
+                            tap or table.
+    --cache DIR             Keep cached inputs in DIR instead of ${} or .inputs.
+    --param [DAY.]KEY=VALUE Override one of a day's parameters, e.g. day09.preamble=5. VALUE is
+                            read as TOML, or as text if it is not valid TOML. Without DAY,
+                            exactly one day must be given. Used by run, bench, check and report.
+    --config PATH           Read parameters from a TOML file with a [dayNN] table for each day.
+                            --param overrides the file.
+    --seed N                Seed the input generator (default: the current time, printed to stderr).
+    --size N                Roughly how many records to generate (default: {}).
+
+fetch downloads from ${}, which must be on this machine, using the session cookie in ${}.
+
+Days may be given by name or number (e.g. day07 or 7).
+check exits with a failure if any answer differs from its expected value or errors.
+
+Available days: {}",
+        input::INPUTS_VAR,
+        input::cache::CACHE_VAR,
+        generate::DEFAULT_SIZE,
+        fetch::ENDPOINT_VAR,
+        fetch::SESSION_VAR,
+        names.join(", ")
+    )
+}
+
+fn select<'a>(
+    registry: &'a Registry,
+    names: &[String],
+) -> Result<Vec<&'a dyn DynChallenge>, String> {
+    if names.is_empty() {
+        return Ok(registry.iter().collect());
+    }
+    names
+        .iter()
+        .map(|name| {
+            registry
+                .get(name)
+                .ok_or_else(|| format!("unknown day '{}'", name))
+        })
+        .collect()
+}
+
+/// The parameters for the selected days, from the `--config` file then each
+/// `--param`.
+fn config(
+    registry: &Registry,
+    selected: &[&dyn DynChallenge],
+    path: Option<&Path>,
+    params: &[Param],
+) -> error::Result<Config> {
+    let mut config = match path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    if let Some(day) = config
+        .days()
+        .find(|&day| registry.get(day).map(|c| c.name()) != Some(day))
+    {
+        return Err(Error::Params(format!("unknown day '{}' in config", day)));
+    }
+    for param in params {
+        let day = match &param.day {
+            Some(day) => registry
+                .get(day)
+                .ok_or_else(|| Error::Params(format!("unknown day '{}'", day)))?,
+            None => selected[0],
+        };
+        config.set(day.name(), &param.key, &param.value);
+    }
+    Ok(config)
+}
+
+fn is_missing(err: &Error) -> bool {
+    matches!(err, Error::MissingInput { .. })
+}
+
+fn expected(resolver: &Resolver, day: &dyn DynChallenge) -> error::Result<Expected> {
+    match resolver.find(day.name(), "expected") {
+        Some(path) => Expected::load(path),
+        None => Ok(Expected::default()),
+    }
+}
+
+pub fn main<I: Iterator<Item = String>>(registry: &Registry, args: I) -> i32 {
+    let command = match parse_args(args) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, usage(registry));
+            return USAGE;
+        }
+    };
+
+    let (selected, resolver) = match &command {
+        Command::Run {
+            days: names,
+            inputs,
+            ..
+        }
+        | Command::Bench {
+            days: names,
+            inputs,
+            ..
+        }
+        | Command::Check {
+            days: names,
+            inputs,
+            ..
+        } => (select(registry, names), Resolver::from_env(inputs.clone())),
+        Command::Fetch { days: names, .. }
+        | Command::Install { days: names, .. }
+        | Command::Generate { days: names, .. } => {
+            (select(registry, names), Resolver::from_env(None))
+        }
+        Command::Report { day, inputs, .. } => (
+            select(registry, std::slice::from_ref(day)),
+            Resolver::from_env(inputs.clone()),
+        ),
+        Command::Import { day, .. } => (
+            select(registry, std::slice::from_ref(day)),
+            Resolver::from_env(None),
+        ),
+        Command::List | Command::Help | Command::Cache { .. } => {
+            (Ok(vec![]), Resolver::from_env(None))
+        }
+    };
+    let selected = match selected {
+        Ok(selected) => selected,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, usage(registry));
+            return USAGE;
+        }
+    };
+
+    let config = match &command {
+        Command::Run { params, config, .. }
+        | Command::Bench { params, config, .. }
+        | Command::Check { params, config, .. }
+        | Command::Report { params, config, .. } => {
+            self::config(registry, &selected, config.as_deref(), params)
+        }
+        _ => Ok(Config::default()),
+    };
+    let config = match config {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return FAILURE;
+        }
+    };
+
+    match command {
+        Command::Help => {
+            println!("{}", usage(registry));
+            SUCCESS
+        }
+        Command::List => {
+            for c in registry.iter() {
+                println!("{}\t{} day {}", c.name(), c.year(), c.day());
+            }
+            SUCCESS
+        }
+        Command::Run {
+            part,
+            input,
+            jobs,
+            format,
+            ..
+        } => {
+            let parts = match &part {
+                Some(part) => std::slice::from_ref(part),
+                None => &Part::ALL[..],
+            };
+            let solve = |day: &&dyn DynChallenge| -> Vec<Record> {
+                let failed = |err: Error| {
+                    parts
+                        .iter()
+                        .map(|&part| {
+                            Record::failed(day.name(), part, report::Status::Error, err.to_string())
+                        })
+                        .collect()
+                };
+                let input = match resolver.open(day.name(), &input) {
+                    Ok(input) => input,
+                    Err(err) => return failed(err),
+                };
+                let (challenge, parse) =
+                    time(|| day.parse_bytes(input.as_bytes(), &config.get(day.name())));
+                let challenge = match challenge {
+                    Ok(challenge) => challenge,
+                    Err(err) => return failed(err),
+                };
+                parts
+                    .iter()
+                    .map(|&part| {
+                        let (answer, elapsed) = time(|| challenge.part(part));
+                        Record::solved(day.name(), part, answer, parse, elapsed)
+                    })
+                    .collect()
+            };
+
+            let stdout = io::stdout();
+            let mut formatter = format.formatter(stdout.lock());
+            let mut code = SUCCESS;
+            let print = |records: Vec<Record>| {
+                if records.iter().any(|r| r.status == report::Status::Error) {
+                    code = FAILURE;
+                }
+                if let Err(err) = formatter.day(&records) {
+                    eprintln!("error: {}", err);
+                    code = FAILURE;
+                }
+            };
+            parallel::for_each_ordered(
+                &selected,
+                jobs.unwrap_or_else(parallel::default_jobs),
+                solve,
+                print,
+            );
+            if let Err(err) = formatter.finish() {
+                eprintln!("error: {}", err);
+                code = FAILURE;
+            }
+            code
+        }
+        Command::Bench {
+            input,
+            options,
+            json,
+            ..
+        } => {
+            let mut code = SUCCESS;
+            let mut measurements = vec![];
+            for day in selected {
+                match resolver.open(day.name(), &input).and_then(|input| {
+                    bench::bench(day, input.as_bytes(), &config.get(day.name()), options)
+                }) {
+                    Ok(m) => {
+                        if m.iter().any(|m| m.error.is_some()) {
+                            code = FAILURE;
+                        }
+                        measurements.extend(m);
+                    }
+                    Err(err) => {
+                        eprintln!("error: {}: {}", day.name(), err);
+                        code = FAILURE;
+                    }
+                }
+            }
+            print!("{}", bench::table(&measurements));
+            if let Some(path) = json {
+                if let Err(err) = fs::write(&path, bench::json(&measurements, options)) {
+                    eprintln!("error: could not write {}: {}", path.display(), err);
+                    code = FAILURE;
+                }
+            }
+            code
+        }
+        Command::Check { jobs, format, .. } => {
+            enum Outcome {
+                Skipped,
+                Failed(String),
+                Checked(check::Report),
+            }
+            let run = |day: &&dyn DynChallenge| {
+                let input = match resolver.open(day.name(), &Source::default()) {
+                    Ok(input) => input,
+                    Err(err) if is_missing(&err) => return Outcome::Skipped,
+                    Err(err) => return Outcome::Failed(err.to_string()),
+                };
+                match expected(&resolver, *day) {
+                    Ok(expected) => Outcome::Checked(check::check(
+                        *day,
+                        input.as_bytes(),
+                        &config.get(day.name()),
+                        &expected,
+                    )),
+                    Err(err) => Outcome::Failed(format!("bad expected answers: {}", err)),
+                }
+            };
+
+            let stdout = io::stdout();
+            let mut formatter = format.formatter(stdout.lock());
+            let mut code = SUCCESS;
+            let (mut passed, mut failed, mut missing, mut skipped) = (0, 0, 0, 0);
+            let mut days = selected.iter();
+            let print = |outcome| {
+                let day = days.next().unwrap();
+                if format != Format::Text {
+                    let records = match outcome {
+                        Outcome::Skipped => Part::ALL
+                            .iter()
+                            .map(|&part| {
+                                let reason = "no input".to_string();
+                                Record::failed(day.name(), part, report::Status::Skip, reason)
+                            })
+                            .collect(),
+                        Outcome::Failed(err) => Part::ALL
+                            .iter()
+                            .map(|&part| {
+                                Record::failed(day.name(), part, report::Status::Error, err.clone())
+                            })
+                            .collect(),
+                        Outcome::Checked(report) => Record::from_check(day.name(), report),
+                    };
+                    if records
+                        .iter()
+                        .any(|r| matches!(r.status, report::Status::Fail | report::Status::Error))
+                    {
+                        code = FAILURE;
+                    }
+                    if let Err(err) = formatter.day(&records) {
+                        eprintln!("error: {}", err);
+                        code = FAILURE;
+                    }
+                    return;
+                }
+
+                let report = match outcome {
+                    Outcome::Skipped => {
+                        println!("{}: skipped (no input)", day.name());
+                        skipped += 1;
+                        return;
+                    }
+                    Outcome::Failed(err) => {
+                        println!("{}: ERROR ({})", day.name(), err);
+                        failed += 1;
+                        code = FAILURE;
+                        return;
+                    }
+                    Outcome::Checked(report) => report,
+                };
+                if report.is_regression() {
+                    code = FAILURE;
+                }
+
+                println!("{} (parse {})", day.name(), format_duration(report.parse));
+                match report.parts {
+                    Ok(parts) => {
+                        for part in parts {
+                            match part.status {
+                                Status::Pass => passed += 1,
+                                Status::Missing(_) => missing += 1,
+                                _ => failed += 1,
+                            }
+                            println!(
+                                "\tpart {}: {} ({})",
+                                part.part,
+                                part.status,
+                                format_duration(part.elapsed)
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        println!("\tERROR ({})", err);
+                        failed += Part::ALL.len();
+                    }
+                }
+            };
+            parallel::for_each_ordered(
+                &selected,
+                jobs.unwrap_or_else(parallel::default_jobs),
+                run,
+                print,
+            );
+            if format == Format::Text {
+                println!(
+                    "\n{} passed, {} failed, {} missing, {} skipped",
+                    passed, failed, missing, skipped
+                );
+            } else if let Err(err) = formatter.finish() {
+                eprintln!("error: {}", err);
+                code = FAILURE;
+            }
+            code
+        }
+        Command::Fetch { cache, .. } => {
+            let client = match Client::from_env() {
+                Ok(client) => client,
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    return FAILURE;
+                }
+            };
+            let cache = Cache::from_env(cache);
+            let mut code = SUCCESS;
+            for day in selected {
+                let url = client.url(day.year(), day.day());
+                let entry = client.fetch(day.year(), day.day()).and_then(|contents| {
+                    cache.import(
+                        day.name(),
+                        input::DEFAULT_NAME,
+                        &contents,
+                        Origin::Url { url },
+                    )
+                });
+                match entry {
+                    Ok(entry) => println!(
+                        "{}: fetched {} bytes ({})",
+                        day.name(),
+                        entry.bytes,
+                        &entry.sha256[..12]
+                    ),
+                    Err(err) => {
+                        eprintln!("error: {}: {}", day.name(), err);
+                        code = FAILURE;
+                    }
+                }
+            }
+            code
+        }
+        Command::Report { input, .. } => {
+            let day = selected[0];
+            let report = resolver.open(day.name(), &input).and_then(|input| {
+                let challenge = day.parse_bytes(input.as_bytes(), &config.get(day.name()))?;
+                Ok(challenge.report())
+            });
+            match report {
+                Ok(Some(report)) => {
+                    println!("{:#}", report);
+                    SUCCESS
+                }
+                Ok(None) => {
+                    eprintln!("error: {} has nothing to report", day.name());
+                    FAILURE
+                }
+                Err(err) => {
+                    eprintln!("error: {}: {}", day.name(), err);
+                    FAILURE
+                }
+            }
+        }
+        Command::Import {
+            from, name, cache, ..
+        } => {
+            let day = selected[0];
+            let origin = match &from {
+                Source::Path(path) => Origin::File {
+                    path: fs::canonicalize(path).unwrap_or_else(|_| path.clone()),
+                },
+                _ => Origin::Stdin,
+            };
+            let entry = resolver.read(day.name(), &from).and_then(|contents| {
+                Cache::from_env(cache).import(day.name(), &name, &contents, origin)
+            });
+            match entry {
+                Ok(entry) => {
+                    println!(
+                        "{}: imported '{}' ({} bytes, {})",
+                        day.name(),
+                        name,
+                        entry.bytes,
+                        &entry.sha256[..12]
+                    );
+                    SUCCESS
+                }
+                Err(err) => {
+                    eprintln!("error: {}: {}", day.name(), err);
+                    FAILURE
+                }
+            }
+        }
+        Command::Install {
+            name,
+            inputs,
+            cache,
+            ..
+        } => {
+            let cache = Cache::from_env(cache);
+            let dir = inputs.unwrap_or_else(input::default_dir);
+            let mut code = SUCCESS;
+            for day in selected {
+                let installed = cache.get(day.name(), &name).and_then(|entry| match entry {
+                    Some(entry) => cache.install(&entry, &dir).map(Some),
+                    None => Ok(None),
+                });
+                match installed {
+                    Ok(Some(path)) => println!("{}: installed {}", day.name(), path.display()),
+                    Ok(None) => println!("{}: nothing cached as '{}'", day.name(), name),
+                    Err(err) => {
+                        eprintln!("error: {}: {}", day.name(), err);
+                        code = FAILURE;
+                    }
+                }
+            }
+            code
+        }
+        Command::Generate {
+            seed, size, inputs, ..
+        } => {
+            let seed = seed.unwrap_or_else(|| {
+                let seed = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_nanos() as u64);
+                eprintln!("seed {}", seed);
+                seed
+            });
+            let mut code = SUCCESS;
+            for day in selected {
+                // every day starts from the seed, so each can be regenerated alone
+                let Some(generated) = day.generate(&mut Rng::new(seed), size) else {
+                    eprintln!("error: {}: no input generator", day.name());
+                    code = FAILURE;
+                    continue;
+                };
+                let Some(dir) = &inputs else {
+                    println!("{}", generated.input);
+                    continue;
+                };
+                let dir = dir.join(day.name());
+                let written = write_file(dir.join("input.txt"), &format!("{}\n", generated.input))
+                    .and_then(|()| {
+                        write_file(dir.join("expected.txt"), &generated.expected.to_string())
+                    });
+                match written {
+                    Ok(()) => println!(
+                        "{}: generated {} (seed {}, size {})",
+                        day.name(),
+                        dir.join("input.txt").display(),
+                        seed,
+                        size
+                    ),
+                    Err(err) => {
+                        eprintln!("error: {}: {}", day.name(), err);
+                        code = FAILURE;
+                    }
+                }
+            }
+            code
+        }
+        Command::Cache { cache } => match Cache::from_env(cache).entries() {
+            Ok(entries) => {
+                for e in entries {
+                    println!(
+                        "{}\t{}\t{}\t{} bytes\t{}",
+                        e.day,
+                        e.name,
+                        &e.sha256[..12],
+                        e.bytes,
+                        e.origin
+                    );
+                }
+                SUCCESS
+            }
+            Err(err) => {
+                eprintln!("error: {}", err);
+                FAILURE
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(
+            parse_args(args(&[])),
+            Ok(Command::Run {
+                days: vec![],
+                part: None,
+                input: Source::default(),
+                inputs: None,
+                jobs: None,
+                format: Format::Text,
+                params: vec![],
+                config: None,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "run",
+                "day07",
+                "--part",
+                "2",
+                "--input",
+                "example.txt"
+            ])),
+            Ok(Command::Run {
+                days: vec!["day07".to_string()],
+                part: Some(Part::Two),
+                input: Source::Path(PathBuf::from("example.txt")),
+                inputs: None,
+                jobs: None,
+                format: Format::Text,
+                params: vec![],
+                config: None,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["run", "-i", "example1", "--inputs", "/tmp/aoc"])),
+            Ok(Command::Run {
+                days: vec![],
+                part: None,
+                input: Source::Named("example1".to_string()),
+                inputs: Some(PathBuf::from("/tmp/aoc")),
+                jobs: None,
+                format: Format::Text,
+                params: vec![],
+                config: None,
+            })
+        );
+        assert_eq!(parse_args(args(&["list"])), Ok(Command::List));
+        assert_eq!(
+            parse_args(args(&["check", "1", "2", "-j", "4", "--format", "tap"])),
+            Ok(Command::Check {
+                days: vec!["1".to_string(), "2".to_string()],
+                inputs: None,
+                jobs: Some(4),
+                format: Format::Tap,
+                params: vec![],
+                config: None,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "bench", "9", "-n", "50", "--warmup", "0", "--json", "out.json"
+            ])),
+            Ok(Command::Bench {
+                days: vec!["9".to_string()],
+                input: Source::default(),
+                inputs: None,
+                options: bench::Options {
+                    warmup: 0,
+                    iterations: 50
+                },
+                json: Some(PathBuf::from("out.json")),
+                params: vec![],
+                config: None,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&[
+                "run",
+                "9",
+                "--param",
+                "preamble=5",
+                "--param",
+                "day01.target=2021",
+                "--config",
+                "params.toml"
+            ])),
+            Ok(Command::Run {
+                days: vec!["9".to_string()],
+                part: None,
+                input: Source::default(),
+                inputs: None,
+                jobs: None,
+                format: Format::Text,
+                params: vec![
+                    "preamble=5".parse().unwrap(),
+                    "day01.target=2021".parse().unwrap()
+                ],
+                config: Some(PathBuf::from("params.toml")),
+            })
+        );
+        assert!(parse_args(args(&["check", "--param", "day09.preamble=5"])).is_ok());
+        assert!(parse_args(args(&["run", "--param", "preamble=5"])).is_err());
+        assert!(parse_args(args(&["run", "1", "--param", "target"])).is_err());
+        assert!(parse_args(args(&["list", "--config", "params.toml"])).is_err());
+        assert!(parse_args(args(&["bench", "--iterations", "0"])).is_err());
+        assert!(parse_args(args(&["bench", "--jobs", "2"])).is_err());
+        assert!(parse_args(args(&["run", "--jobs", "0"])).is_err());
+        assert!(parse_args(args(&["run", "--format", "xml"])).is_err());
+        assert!(parse_args(args(&["bench", "--format", "json"])).is_err());
+        assert!(parse_args(args(&["bench", "--part", "1"])).is_err());
+        assert!(parse_args(args(&["check", "--warmup", "1"])).is_err());
+        assert_eq!(
+            parse_args(args(&["import", "1", "--from", "-", "-i", "large"])),
+            Ok(Command::Import {
+                day: "1".to_string(),
+                from: Source::Stdin,
+                name: "large".to_string(),
+                cache: None,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["install", "--inputs", "inputs", "--cache", "cache"])),
+            Ok(Command::Install {
+                days: vec![],
+                name: "input".to_string(),
+                inputs: Some(PathBuf::from("inputs")),
+                cache: Some(PathBuf::from("cache")),
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["generate", "9", "--seed", "42", "--size", "10"])),
+            Ok(Command::Generate {
+                days: vec!["9".to_string()],
+                seed: Some(42),
+                size: 10,
+                inputs: None,
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["generate", "--inputs", "stress"])),
+            Ok(Command::Generate {
+                days: vec![],
+                seed: None,
+                size: generate::DEFAULT_SIZE,
+                inputs: Some(PathBuf::from("stress")),
+            })
+        );
+        assert!(parse_args(args(&["generate", "1", "2"])).is_err());
+        assert!(parse_args(args(&["generate", "1", "--seed", "-1"])).is_err());
+        assert!(parse_args(args(&["generate", "1", "--size", "0"])).is_err());
+        assert!(parse_args(args(&["run", "--seed", "1"])).is_err());
+        assert_eq!(
+            parse_args(args(&["report", "4", "--param", "schema=strict.toml"])),
+            Ok(Command::Report {
+                day: "4".to_string(),
+                input: Source::default(),
+                inputs: None,
+                params: vec!["schema=strict.toml".parse().unwrap()],
+                config: None,
+            })
+        );
+        assert!(parse_args(args(&["report", "4", "5"])).is_err());
+        assert!(parse_args(args(&["report", "4", "--part", "2"])).is_err());
+        assert!(parse_args(args(&["import", "1"])).is_err());
+        assert!(parse_args(args(&["import", "1", "2", "--from", "a.txt"])).is_err());
+        assert!(parse_args(args(&["install", "1", "-i", "a.txt"])).is_err());
+        assert!(parse_args(args(&["fetch", "--from", "a.txt"])).is_err());
+        assert!(parse_args(args(&["cache", "1"])).is_err());
+        assert!(parse_args(args(&["run", "--part", "3"])).is_err());
+        assert!(parse_args(args(&["run", "--input", "a.txt"])).is_err());
+        assert!(parse_args(args(&["run", "1", "2", "--input", "-"])).is_err());
+        assert!(parse_args(args(&["check", "--input", "example1"])).is_err());
+        assert!(parse_args(args(&["list", "day01"])).is_err());
+        assert!(parse_args(args(&["frobnicate"])).is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let registry = Registry::new()
+            .register::<crate::day01::Day01>()
+            .register::<crate::day13::Day13>();
+        let selected = select(&registry, &["13".to_string(), "day01".to_string()]).unwrap();
+        let names: Vec<_> = selected.iter().map(|c| c.name()).collect();
+        assert_eq!(names, vec!["day13", "day01"]);
+
+        assert!(select(&registry, &["day02".to_string()]).is_err());
+    }
+}
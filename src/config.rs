@@ -0,0 +1,239 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::de::DeserializeOwned;
+use toml::{value::Table, Value};
+
+use crate::{
+    error::{Error, Result},
+    read_file,
+};
+
+/// Settings for one challenge that replace the puzzle's own, such as the sum
+/// day 1 looks for. A challenge reads them into its own `Params` struct, whose
+/// [`Default`] holds the puzzle's values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Overrides {
+    values: Table,
+    /// The directory of the config file each setting was read from.
+    dirs: BTreeMap<String, PathBuf>,
+}
+
+impl Overrides {
+    /// Sets `key` to `value`, read as TOML if it can be and as a string
+    /// otherwise, so `bag=shiny gold` needs no quotes but `slope=[3, 1]` is a
+    /// list.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let value = toml::from_str::<Table>(&format!("value = {}", value))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .unwrap_or_else(|| Value::String(value.to_string()));
+        self.values.insert(key.to_string(), value);
+        self.dirs.remove(key);
+    }
+
+    /// Where the setting `key` finds `path`: relative to the config file it was
+    /// read from, or to the current directory if it was set anywhere else.
+    pub fn path(&self, key: &str, path: &Path) -> PathBuf {
+        match self.dirs.get(key) {
+            Some(dir) => dir.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Fills in `T` from these overrides and its defaults.
+    pub fn apply<T: DeserializeOwned>(&self) -> Result<T> {
+        Value::Table(self.values.clone())
+            .try_into()
+            .map_err(|err| Error::Params(err.to_string()))
+    }
+
+    /// Fails for a challenge with nothing to override.
+    pub fn none(&self) -> Result<()> {
+        match self.values.keys().next() {
+            Some(key) => Err(Error::Params(format!("unknown field `{}`", key))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Reads `key = value` lines in TOML.
+impl FromStr for Overrides {
+    type Err = toml::de::Error;
+
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let values = toml::from_str(contents)?;
+        Ok(Overrides {
+            values,
+            dirs: BTreeMap::new(),
+        })
+    }
+}
+
+/// The overrides for every challenge, read from a TOML file with a table for
+/// each challenge:
+///
+/// ```toml
+/// [day01]
+/// target = 2021
+///
+/// [day07]
+/// bag = "dark olive"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Config(BTreeMap<String, Overrides>);
+
+impl Config {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = read_file(path)?;
+        let mut config: Config = contents
+            .parse()
+            .map_err(|err| Error::Params(format!("{}: {}", path.display(), err)))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for overrides in config.0.values_mut() {
+            overrides.dirs = overrides
+                .values
+                .keys()
+                .map(|key| (key.clone(), dir.to_path_buf()))
+                .collect();
+        }
+        Ok(config)
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    pub fn set(&mut self, day: &str, key: &str, value: &str) {
+        self.0.entry(day.to_string()).or_default().set(key, value);
+    }
+
+    pub fn get(&self, day: &str) -> Overrides {
+        self.0.get(day).cloned().unwrap_or_default()
+    }
+}
+
+impl FromStr for Config {
+    type Err = toml::de::Error;
+
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let days: BTreeMap<String, Table> = toml::from_str(contents)?;
+        Ok(Config(
+            days.into_iter()
+                .map(|(day, values)| {
+                    let dirs = BTreeMap::new();
+                    (day, Overrides { values, dirs })
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// A `--param [DAY.]KEY=VALUE` from the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub day: Option<String>,
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for Param {
+    type Err = String;
+
+    fn from_str(param: &str) -> Result<Self, Self::Err> {
+        let (name, value) = param
+            .split_once('=')
+            .ok_or_else(|| format!("invalid parameter '{}', expected KEY=VALUE", param))?;
+        let (day, key) = match name.split_once('.') {
+            Some((day, key)) => (Some(day.to_string()), key),
+            None => (None, name),
+        };
+        Ok(Param {
+            day,
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+}
+
+#[test]
+fn test_overrides() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    struct Params {
+        target: i64,
+        slope: (usize, usize),
+        bag: String,
+    }
+    impl Default for Params {
+        fn default() -> Self {
+            Params {
+                target: 2020,
+                slope: (3, 1),
+                bag: "shiny gold".to_string(),
+            }
+        }
+    }
+
+    let mut overrides = Overrides::default();
+    assert_eq!(overrides.apply::<Params>().unwrap(), Params::default());
+    assert!(overrides.none().is_ok());
+
+    overrides.set("slope", "[1, 2]");
+    overrides.set("bag", "dark olive");
+    assert_eq!(
+        overrides.apply::<Params>().unwrap(),
+        Params {
+            slope: (1, 2),
+            bag: "dark olive".to_string(),
+            ..Params::default()
+        }
+    );
+    assert!(overrides.none().is_err());
+
+    overrides.set("target", "twenty");
+    assert!(overrides.apply::<Params>().is_err());
+    let overrides: Overrides = "trget = 5".parse().unwrap();
+    assert!(overrides.apply::<Params>().is_err());
+}
+
+#[test]
+fn test_config() {
+    let mut config: Config = "[day01]\ntarget = 2021\n\n[day07]\nbag = \"dark olive\""
+        .parse()
+        .unwrap();
+    config.set("day01", "k", "[3, 4]");
+    assert_eq!(config.days().collect::<Vec<_>>(), vec!["day01", "day07"]);
+    assert_eq!(
+        config.get("day01"),
+        "target = 2021\nk = [3, 4]".parse().unwrap()
+    );
+    assert_eq!(config.get("day02"), Overrides::default());
+    assert!("target = 2021".parse::<Config>().is_err());
+
+    let dir = std::env::temp_dir().join(format!("aoc-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("params.toml");
+    std::fs::write(&path, "[day04]\nschema = \"passports.toml\"").unwrap();
+    let mut config = Config::load(&path).unwrap();
+    let schema = Path::new("passports.toml");
+    assert_eq!(config.get("day04").path("schema", schema), dir.join(schema));
+    config.set("day04", "schema", "passports.toml");
+    assert_eq!(config.get("day04").path("schema", schema), schema);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        "day07.bag=dark olive".parse(),
+        Ok(Param {
+            day: Some("day07".to_string()),
+            key: "bag".to_string(),
+            value: "dark olive".to_string(),
+        })
+    );
+    assert_eq!("target=1".parse::<Param>().unwrap().day, None);
+    assert!("target".parse::<Param>().is_err());
+}
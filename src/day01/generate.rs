@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use super::ksum::{self, Method};
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+/// An expense report with exactly one pair and one triple of entries that sum
+/// to 2020.
+///
+/// The triple is made of small entries and everything else is over 1010, so no
+/// other pair or triple can reach 2020 once the few sums that would are avoided.
+/// There are only so many entries over 1010, so `size` is capped at 900.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let (small, pair) = loop {
+        let a = rng.range(10..1000);
+        let x = rng.range(400..1000);
+        let y = rng.range(1020 - x..1000);
+        let z = 2020 - x - y;
+        let small = [a, x, y, z];
+        let distinct = small.iter().collect::<HashSet<_>>().len() == 4;
+        // a must not complete a triple with two of the others
+        if distinct
+            && [x + y, x + z, y + z]
+                .iter()
+                .all(|&s| s + a != 2020 && s != a)
+        {
+            break (small, (a, 2020 - a));
+        }
+    };
+
+    // entries over 1010 that would complete a pair or a triple with the small ones
+    let mut avoid: HashSet<usize> = small.iter().map(|s| 2020 - s).collect();
+    for (i, s) in small.iter().enumerate() {
+        avoid.extend(small[i + 1..].iter().map(|t| 2020 - s - t));
+    }
+    let mut large: Vec<usize> = (1011..2020).filter(|n| !avoid.contains(n)).collect();
+    rng.shuffle(&mut large);
+    large.truncate(size.clamp(5, 900) - 5);
+
+    let mut entries: Vec<usize> = small.to_vec();
+    entries.push(pair.1);
+    entries.extend(large);
+    rng.shuffle(&mut entries);
+
+    debug_assert_eq!(ksum::all(&entries, 2020, 2, Method::Hash).len(), 1);
+    debug_assert_eq!(ksum::all(&entries, 2020, 3, Method::TwoPointer).len(), 1);
+
+    let input: Vec<String> = entries.iter().map(usize::to_string).collect();
+    let [_, x, y, z] = small;
+    Generated::new(input.join("\n"))
+        .answer(Part::One, pair.0 * pair.1)
+        .answer(Part::Two, x * y * z)
+}
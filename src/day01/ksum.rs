@@ -0,0 +1,349 @@
+//! Finding `k` entries of a list that add up to a target.
+//!
+//! Every method treats entries at different positions as different, so a
+//! value listed twice can be used twice but a value listed once cannot. A
+//! solution is a set of positions, and [`all`] returns each set once.
+
+use std::{collections::HashMap, fmt::Debug, hash::Hash, ops::ControlFlow};
+
+/// An integer type that fits in an `i128`, where sums are worked out so they
+/// cannot overflow.
+pub trait Int: Copy + Ord + Hash + Debug {
+    fn widen(self) -> i128;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                fn widen(self) -> i128 {
+                    self as i128
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Method {
+    /// Tries every choice of the first `k - 2` entries, then looks up the
+    /// last in a hash map: O(n^(k-1)) time and O(n) space.
+    Hash,
+    /// Sorts the entries and closes in on the last two from both ends:
+    /// O(n^(k-1)) time without hashing, and O(n) space.
+    TwoPointer,
+    /// Sums every choice of `k / 2` entries up front and looks up the rest:
+    /// O(n^ceil(k/2)) time but O(n^floor(k/2)) space.
+    MeetInTheMiddle,
+}
+
+/// The most entries meet in the middle will tabulate sums for.
+const MAX_TABLE: usize = 1 << 20;
+
+impl Method {
+    /// A good method for `k` entries out of `n`.
+    pub fn choose(n: usize, k: usize) -> Method {
+        match k {
+            0..=2 => Method::Hash,
+            3 => Method::TwoPointer,
+            _ if combinations(n, k / 2) <= MAX_TABLE => Method::MeetInTheMiddle,
+            _ => Method::TwoPointer,
+        }
+    }
+}
+
+/// The number of ways to choose `k` of `n`, saturating.
+fn combinations(n: usize, k: usize) -> usize {
+    (0..k.min(n)).fold(1usize, |c, i| c.saturating_mul(n - i) / (i + 1))
+}
+
+/// Some solution, found without looking for the others.
+pub fn first<T: Int>(numbers: &[T], target: T, k: usize, method: Method) -> Option<Vec<T>> {
+    let mut found = None;
+    search(numbers, target, k, method, &mut |solution| {
+        found = Some(sorted(solution));
+        ControlFlow::Break(())
+    });
+    found.map(|solution| values(numbers, solution))
+}
+
+/// Every solution, ordered by the positions of their entries.
+pub fn all<T: Int>(numbers: &[T], target: T, k: usize, method: Method) -> Vec<Vec<T>> {
+    let mut found = vec![];
+    search(numbers, target, k, method, &mut |solution| {
+        found.push(sorted(solution));
+        ControlFlow::Continue(())
+    });
+    found.sort_unstable();
+    found
+        .into_iter()
+        .map(|solution| values(numbers, solution))
+        .collect()
+}
+
+fn sorted(positions: &[usize]) -> Vec<usize> {
+    let mut positions = positions.to_vec();
+    positions.sort_unstable();
+    positions
+}
+
+fn values<T: Int>(numbers: &[T], positions: Vec<usize>) -> Vec<T> {
+    positions.into_iter().map(|i| numbers[i]).collect()
+}
+
+/// Calls `emit` with the positions of each solution until it breaks.
+type Emit<'e> = dyn FnMut(&[usize]) -> ControlFlow<()> + 'e;
+
+fn search<T: Int>(numbers: &[T], target: T, k: usize, method: Method, emit: &mut Emit) {
+    let numbers: Vec<i128> = numbers.iter().map(|n| n.widen()).collect();
+    let target = target.widen();
+    let _ = match k {
+        0 if target == 0 => emit(&[]),
+        0 => ControlFlow::Continue(()),
+        1 => (0..numbers.len())
+            .filter(|&i| numbers[i] == target)
+            .try_for_each(|i| emit(&[i])),
+        _ => match method {
+            Method::Hash => {
+                let mut positions: HashMap<i128, Vec<usize>> = HashMap::new();
+                for (i, &n) in numbers.iter().enumerate() {
+                    positions.entry(n).or_default().push(i);
+                }
+                hash(&numbers, &positions, target, k, 0, &mut vec![], emit)
+            }
+            Method::TwoPointer => {
+                let mut order: Vec<usize> = (0..numbers.len()).collect();
+                order.sort_unstable_by_key(|&i| (numbers[i], i));
+                let sorted: Vec<i128> = order.iter().map(|&i| numbers[i]).collect();
+                let mut emit = |chosen: &[usize]| {
+                    let positions: Vec<usize> = chosen.iter().map(|&p| order[p]).collect();
+                    emit(&positions)
+                };
+                two_pointer(&sorted, target, k, 0, &mut vec![], &mut emit)
+            }
+            Method::MeetInTheMiddle => meet_in_the_middle(&numbers, target, k, emit),
+        },
+    };
+}
+
+/// Chooses entries from `start` on until two are left, then looks up the
+/// second of the pair for each first.
+fn hash(
+    numbers: &[i128],
+    positions: &HashMap<i128, Vec<usize>>,
+    target: i128,
+    k: usize,
+    start: usize,
+    chosen: &mut Vec<usize>,
+    emit: &mut Emit,
+) -> ControlFlow<()> {
+    for i in start..numbers.len() {
+        chosen.push(i);
+        if k > 2 {
+            hash(
+                numbers,
+                positions,
+                target - numbers[i],
+                k - 1,
+                i + 1,
+                chosen,
+                emit,
+            )?;
+        } else if let Some(matches) = positions.get(&(target - numbers[i])) {
+            for &j in matches.iter().filter(|&&j| j > i) {
+                chosen.push(j);
+                emit(chosen)?;
+                chosen.pop();
+            }
+        }
+        chosen.pop();
+    }
+    ControlFlow::Continue(())
+}
+
+/// The same as [`hash`], but over `sorted` entries, closing in on each pair
+/// from both ends. Emits positions in `sorted`.
+fn two_pointer(
+    sorted: &[i128],
+    target: i128,
+    k: usize,
+    start: usize,
+    chosen: &mut Vec<usize>,
+    emit: &mut Emit,
+) -> ControlFlow<()> {
+    if k > 2 {
+        for i in start..sorted.len() {
+            chosen.push(i);
+            two_pointer(sorted, target - sorted[i], k - 1, i + 1, chosen, emit)?;
+            chosen.pop();
+        }
+        return ControlFlow::Continue(());
+    }
+
+    let (mut lo, mut hi) = (start, sorted.len());
+    while lo + 1 < hi {
+        let sum = sorted[lo] + sorted[hi - 1];
+        if sum < target {
+            lo += 1;
+        } else if sum > target {
+            hi -= 1;
+        } else if sorted[lo] == sorted[hi - 1] {
+            // every pair in between matches
+            for i in lo..hi {
+                for j in i + 1..hi {
+                    emit_pair(chosen, i, j, emit)?;
+                }
+            }
+            break;
+        } else {
+            // pair up the runs of equal entries at either end
+            let low_run = (lo..hi).take_while(|&i| sorted[i] == sorted[lo]).count();
+            let high_run = (lo..hi)
+                .rev()
+                .take_while(|&j| sorted[j] == sorted[hi - 1])
+                .count();
+            for i in lo..lo + low_run {
+                for j in hi - high_run..hi {
+                    emit_pair(chosen, i, j, emit)?;
+                }
+            }
+            lo += low_run;
+            hi -= high_run;
+        }
+    }
+    ControlFlow::Continue(())
+}
+
+fn emit_pair(chosen: &mut Vec<usize>, i: usize, j: usize, emit: &mut Emit) -> ControlFlow<()> {
+    chosen.extend([i, j]);
+    let flow = emit(chosen);
+    chosen.truncate(chosen.len() - 2);
+    flow
+}
+
+/// Splits each solution into its first `k / 2` positions and the rest. The
+/// sums of every possible first half are tabulated, then each second half
+/// looks up the first halves that end before it starts.
+fn meet_in_the_middle(
+    numbers: &[i128],
+    target: i128,
+    k: usize,
+    emit: &mut Emit,
+) -> ControlFlow<()> {
+    let mut halves: HashMap<i128, Vec<Vec<usize>>> = HashMap::new();
+    choose(numbers.len(), k / 2, 0, &mut vec![], &mut |half| {
+        let sum = half.iter().map(|&i| numbers[i]).sum();
+        halves.entry(sum).or_default().push(half.to_vec());
+        ControlFlow::Continue(())
+    })?;
+
+    choose(numbers.len(), k - k / 2, 0, &mut vec![], &mut |rest| {
+        let sum: i128 = rest.iter().map(|&i| numbers[i]).sum();
+        let Some(firsts) = halves.get(&(target - sum)) else {
+            return ControlFlow::Continue(());
+        };
+        for first in firsts.iter().filter(|first| first.last() < rest.first()) {
+            let positions: Vec<usize> = first.iter().chain(rest).copied().collect();
+            emit(&positions)?;
+        }
+        ControlFlow::Continue(())
+    })
+}
+
+/// Calls `f` with every increasing choice of `k` positions from `start` on.
+fn choose(
+    n: usize,
+    k: usize,
+    start: usize,
+    chosen: &mut Vec<usize>,
+    f: &mut Emit,
+) -> ControlFlow<()> {
+    if k == 0 {
+        return f(chosen);
+    }
+    for i in start..n {
+        chosen.push(i);
+        choose(n, k - 1, i + 1, chosen, f)?;
+        chosen.pop();
+    }
+    ControlFlow::Continue(())
+}
+
+#[cfg(test)]
+const METHODS: [Method; 3] = [Method::Hash, Method::TwoPointer, Method::MeetInTheMiddle];
+
+#[test]
+fn test_all() {
+    for &method in METHODS.iter() {
+        let numbers = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(all(&numbers, 2020, 2, method), vec![vec![1721, 299]]);
+        assert_eq!(all(&numbers, 2020, 3, method), vec![vec![979, 366, 675]]);
+        assert_eq!(all(&numbers, 2020, 4, method), Vec::<Vec<i32>>::new());
+
+        // each listed entry may be used once
+        assert_eq!(
+            all(&[1010, 5, 1010], 2020, 2, method),
+            vec![vec![1010, 1010]]
+        );
+        assert_eq!(all(&[1010, 5], 2020, 2, method), Vec::<Vec<i32>>::new());
+        assert_eq!(all(&[2, 2, 2], 4, 2, method).len(), 3);
+
+        assert_eq!(all(&[-3i8, 7, 0, -4, 3], 0, 2, method), vec![vec![-3, 3]]);
+        assert_eq!(
+            all(&[-3i8, 7, 0, -4, 3], 0, 3, method),
+            vec![vec![-3, 7, -4], vec![-3, 0, 3]]
+        );
+        // the sums of u8s do not wrap
+        assert_eq!(all(&[200u8, 100, 44], 44, 2, method), Vec::<Vec<u8>>::new());
+
+        assert_eq!(all(&[5u64], 0, 0, method), vec![Vec::<u64>::new()]);
+        assert_eq!(all(&[5u64, 5], 5, 1, method), vec![vec![5], vec![5]]);
+    }
+}
+
+#[test]
+fn test_first() {
+    for &method in METHODS.iter() {
+        let numbers = [1721, 979, 366, 299, 675, 1456];
+        assert_eq!(first(&numbers, 2020, 2, method), Some(vec![1721, 299]));
+        assert_eq!(first(&numbers, 2020, 3, method), Some(vec![979, 366, 675]));
+        assert_eq!(first(&numbers, 2021, 3, method), None);
+    }
+}
+
+#[test]
+fn test_choose() {
+    assert_eq!(Method::choose(200, 2), Method::Hash);
+    assert_eq!(Method::choose(200, 3), Method::TwoPointer);
+    assert_eq!(Method::choose(200, 4), Method::MeetInTheMiddle);
+    assert_eq!(Method::choose(200, 8), Method::TwoPointer);
+    assert_eq!(combinations(200, 2), 19900);
+}
+
+/// Every method finds the same solutions as trying every choice of entries.
+#[test]
+fn test_methods_agree() {
+    use proptest::collection::vec;
+
+    let brute = |(numbers, target, k): &(Vec<i16>, i16, usize)| {
+        let mut found = vec![];
+        let _ = choose(numbers.len(), *k, 0, &mut vec![], &mut |positions| {
+            let sum: i128 = positions.iter().map(|&i| numbers[i] as i128).sum();
+            if sum == *target as i128 {
+                found.push(positions.iter().map(|&i| numbers[i]).collect());
+            }
+            ControlFlow::Continue(())
+        });
+        found
+    };
+    let inputs = (vec(-20i16..20, 0..12), -40i16..40, 0..5usize);
+    for &method in METHODS.iter() {
+        crate::differential::agree(
+            inputs.clone(),
+            |(numbers, target, k)| all(numbers, *target, *k, method),
+            brute,
+        );
+    }
+}
@@ -1,76 +1,95 @@
-use crate::Challenge;
-use nom::{
-    character::complete::{digit1, line_ending},
-    combinator::map_res,
-    multi::separated_list1,
-    IResult,
+use serde::Deserialize;
+
+use crate::{
+    answer::Answer,
+    config::Overrides,
+    error::{Error, Result},
+    generate::{Generated, Rng},
+    parsers::{all, lines, signed},
+    Challenge,
 };
+use ksum::Method;
+
+mod generate;
+pub mod ksum;
 
 pub struct Day01 {
-    numbers: Vec<usize>,
+    numbers: Vec<i64>,
+    params: Params,
 }
 
-impl Challenge for Day01 {
-    fn name() -> &'static str {
-        "day01"
-    }
-    fn new(input: String) -> Self {
-        Day01 {
-            numbers: parse_numbers(&input).unwrap().1,
-        }
-    }
-    fn part_one(&self) -> usize {
-        find_sum(&self.numbers, 2020, 2).unwrap().iter().product()
-    }
-    fn part_two(&self) -> usize {
-        find_sum(&self.numbers, 2020, 3).unwrap().iter().product()
-    }
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// What the entries must add up to.
+    pub target: i64,
+    /// How many entries parts one and two add up.
+    pub k: [usize; 2],
 }
 
-fn parse_number(input: &str) -> IResult<&str, usize> {
-    map_res(digit1, |s: &str| s.parse::<usize>())(input)
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            target: 2020,
+            k: [2, 3],
+        }
+    }
 }
 
-fn parse_numbers(input: &str) -> IResult<&str, Vec<usize>> {
-    separated_list1(line_ending, parse_number)(input)
+impl Day01 {
+    /// The product of the first `k` entries found that add up to the target.
+    fn product(&self, k: usize) -> Result<Answer> {
+        let target = self.params.target;
+        let method = Method::choose(self.numbers.len(), k);
+        let entries = ksum::first(&self.numbers, target, k, method)
+            .ok_or_else(|| Error::no_solution(format!("no {} entries sum to {}", k, target)))?;
+        entries
+            .iter()
+            .try_fold(1i128, |product, &n| product.checked_mul(n.into()))
+            .map(Answer::from)
+            .ok_or_else(|| Error::no_solution("the product of the entries overflows"))
+    }
 }
 
-fn find_sum<T>(numbers: &[T], sum: T, n: usize) -> Option<Vec<T>>
-where
-    T: std::ops::Add<Output = T> + std::ops::Sub<Output = T> + PartialEq + PartialOrd + Copy,
-{
-    match n {
-        0 => Some(vec![]),
-        1 => {
-            if numbers.contains(&sum) {
-                Some(vec![sum])
-            } else {
-                None
-            }
-        }
-        _ => {
-            for i in 0..numbers.len() {
-                if numbers[i] < sum {
-                    let attempt = find_sum(&numbers[i + 1..], sum - numbers[i], n - 1);
-                    if let Some(mut v) = attempt {
-                        v.push(numbers[i]);
-                        return Some(v);
-                    }
-                }
-            }
-            None
-        }
+impl Challenge<'_> for Day01 {
+    fn name() -> &'static str {
+        "day01"
+    }
+    fn day() -> u8 {
+        1
+    }
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day01 {
+            numbers: all(lines(signed), input)?,
+            params: Params::default(),
+        })
+    }
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        self.params = overrides.apply()?;
+        Ok(())
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+    fn part_one(&self) -> Result<Answer> {
+        self.product(self.params.k[0])
+    }
+    fn part_two(&self) -> Result<Answer> {
+        self.product(self.params.k[1])
     }
 }
 
 #[test]
-fn find_sum_pair_test() {
-    let output = find_sum(&vec![1721, 979, 366, 299, 675, 1456], 2020, 2);
-    assert_eq!(output, Some(vec![299, 1721]));
-}
+fn test_day01() {
+    let day = Day01::new("1721\n979\n366\n299\n675\n1456").unwrap();
+    assert_eq!(day.part_one().unwrap(), 514579);
+    assert_eq!(day.part_two().unwrap(), 241861950);
 
-#[test]
-fn find_sum_trio_test() {
-    let output = find_sum(&vec![1721, 979, 366, 299, 675, 1456], 2020, 3);
-    assert_eq!(output, Some(vec![675, 366, 979]));
+    let mut day = Day01::new("-3\n7\n0\n-4\n+3").unwrap();
+    day.params.target = 0;
+    assert_eq!(day.part_one().unwrap(), -9);
+    assert_eq!(day.part_two().unwrap(), 84);
+    day.params.k = [4, 5];
+    assert_eq!(day.part_one().unwrap(), 0);
+    assert!(day.part_two().is_err());
 }
@@ -0,0 +1,33 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+/// Random password policies, about half of them valid under each rule.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut lines = vec![];
+    let (mut valid_counts, mut valid_positions) = (0, 0);
+    for _ in 0..size.max(1) {
+        let c = (b'a' + rng.range(0..5) as u8) as char;
+        let len = rng.range(3..20);
+        let min = rng.range(1..len);
+        let max = rng.range(min + 1..len + 1);
+        // a small alphabet makes the policy letter common
+        let password: String = (0..len)
+            .map(|_| (b'a' + rng.range(0..5) as u8) as char)
+            .collect();
+
+        let count = password.chars().filter(|&p| p == c).count();
+        if (min..=max).contains(&count) {
+            valid_counts += 1;
+        }
+        let at = |i: usize| password.as_bytes()[i - 1] as char == c;
+        if at(min) != at(max) {
+            valid_positions += 1;
+        }
+        lines.push(format!("{}-{} {}: {}", min, max, c, password));
+    }
+    Generated::new(lines.join("\n"))
+        .answer(Part::One, valid_counts)
+        .answer(Part::Two, valid_positions)
+}
@@ -1,96 +1,180 @@
-use crate::Challenge;
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::{
+    answer::Answer,
+    error::Result,
+    generate::{Generated, Rng},
+    parsers::{all, lines, IResult},
+    Challenge, Part, Solver,
+};
 
 use nom::{
-    bytes::complete::tag,
-    character::complete::{alpha1, anychar, char, digit1, line_ending},
-    combinator::map_res,
-    multi::separated_list1,
+    bytes::complete::{tag, take_while1},
+    combinator::consumed,
     sequence::separated_pair,
-    IResult,
 };
 
-pub struct Day02 {
-    records: Vec<Record>,
+use policy::{parse_policy, Policy};
+
+mod generate;
+pub mod policy;
+
+pub struct Day02<'a> {
+    records: Vec<Record<'a>>,
+}
+
+impl Solver for Day02<'static> {
+    type For<'a> = Day02<'a>;
 }
 
-impl Challenge for Day02 {
+impl<'a> Challenge<'a> for Day02<'a> {
     fn name() -> &'static str {
         "day02"
     }
-    fn new(input: String) -> Self{
-        Day02 {
-            records: parse_records(&input).unwrap().1,
-        }
+    fn day() -> u8 {
+        2
     }
-    fn part_one(&self) -> usize {
-        self.records
-            .iter()
-            .filter(|&record| record.is_valid_1())
-            .count()
+    fn new(input: &'a str) -> Result<Self> {
+        Ok(Day02 {
+            records: all(lines(parse_record), input)?,
+        })
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+    fn part_one(&self) -> Result<Answer> {
+        Ok(self.valid(Part::One).into())
+    }
+    fn part_two(&self) -> Result<Answer> {
+        Ok(self.valid(Part::Two).into())
     }
-    fn part_two(&self) -> usize {
+    fn report(&self) -> Option<Value> {
+        let part = |part| {
+            let failures: Vec<Failure> = self.failures(part).collect();
+            json!({
+                "passwords": self.records.len(),
+                "valid": self.records.len() - failures.len(),
+                "failures": failures,
+            })
+        };
+        Some(json!({
+            "part_one": part(Part::One),
+            "part_two": part(Part::Two),
+        }))
+    }
+}
+
+impl Day02<'_> {
+    /// The number of passwords that pass their policy as `part` reads it.
+    fn valid(&self, part: Part) -> usize {
+        self.records.len() - self.failures(part).count()
+    }
+
+    /// Every password that fails its policy as `part` reads it, and why.
+    pub fn failures(&self, part: Part) -> impl Iterator<Item = Failure<'_>> {
         self.records
             .iter()
-            .filter(|&record| record.is_valid_2())
-            .count()
+            .enumerate()
+            .filter_map(move |(i, record)| {
+                record
+                    .policy(part)
+                    .check(record.password)
+                    .err()
+                    .map(|reason| Failure {
+                        line: i + 1,
+                        password: record.password,
+                        reason,
+                    })
+            })
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Policy {
-    min: usize,
-    max: usize,
-    c: char,
+/// A password that fails its policy.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Failure<'a> {
+    /// Where the record is in the input, counting from 1.
+    pub line: usize,
+    pub password: &'a str,
+    pub reason: String,
 }
 
-#[derive(Debug, Clone)]
-struct Record {
-    policy: Policy,
-    password: String,
+impl fmt::Display for Failure<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}: {}", self.line, self.password, self.reason)
+    }
 }
 
-fn parse_number(input: &str) -> IResult<&str, usize> {
-    map_res(digit1, |s: &str| s.parse::<usize>())(input)
+/// A password and its policy, as read by each part.
+struct Record<'a> {
+    one: Policy<'a>,
+    two: Policy<'a>,
+    password: &'a str,
 }
 
-fn parse_policy(input: &str) -> IResult<&str, Policy> {
-    let (input, ((min, max), c)) = separated_pair(
-        separated_pair(parse_number, char('-'), parse_number),
-        char(' '),
-        anychar,
-    )(input)?;
-
-    Ok((input, Policy { min, max, c }))
+impl<'a> Record<'a> {
+    /// The policy as `part` reads it.
+    fn policy(&self, part: Part) -> &Policy<'a> {
+        match part {
+            Part::One => &self.one,
+            Part::Two => &self.two,
+        }
+    }
 }
 
-fn parse_record(input: &str) -> IResult<&str, Record> {
-    let (input, (policy, password)) = separated_pair(parse_policy, tag(": "), alpha1)(input)?;
-
-    Ok((input, Record { policy, password: password.to_string() }))
+/// Any run of characters other than whitespace.
+fn password(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| !c.is_whitespace())(input)
 }
 
-fn parse_records(input: &str) -> IResult<&str, Vec<Record>> {
-    separated_list1(line_ending, parse_record)(input)
+fn parse_record(input: &str) -> IResult<&str, Record<'_>> {
+    let (input, ((text, one), password)) =
+        separated_pair(consumed(parse_policy(Part::One)), tag(": "), password)(input)?;
+    let (_, two) = parse_policy(Part::Two)(text)?;
+
+    Ok((input, Record { one, two, password }))
 }
 
-impl Record {
-    fn is_valid_1(&self) -> bool {
-        let count = self
-            .password
-            .chars()
-            .filter(|&c| c == self.policy.c)
-            .count();
-        self.policy.min <= count && count <= self.policy.max
-    }
+#[test]
+fn test_day02() {
+    let day = Day02::new("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n2-9 c: cc").unwrap();
+    assert_eq!(day.part_one().unwrap(), 3);
+    assert_eq!(day.part_two().unwrap(), 1);
+    let failures: Vec<String> = day.failures(Part::Two).map(|f| f.to_string()).collect();
+    assert_eq!(
+        failures,
+        vec![
+            "line 2: cdefg: has 'b' at neither 1 nor 3",
+            "line 3: ccccccccc: has 'c' at both 2 and 9",
+            "line 4: cc: has no position 9, only 2 characters",
+        ]
+    );
+    let report = day.report().unwrap();
+    assert_eq!(report["part_one"]["valid"], 3);
+    assert_eq!(
+        report["part_one"]["failures"],
+        json!([{ "line": 2, "password": "cdefg", "reason": "has 0 'b', needs 1 to 3" }])
+    );
+    assert_eq!(report["part_two"]["passwords"], 4);
+    assert_eq!(report["part_two"]["failures"][2]["line"], 4);
 
-    fn is_valid_2(&self) -> bool {
-        let mut chars = self.password.chars().skip(self.policy.min - 1);
-        let a = chars.next().unwrap();
-        let b = chars
-            .skip(self.policy.max - self.policy.min - 1)
-            .next()
-            .unwrap();
+    let day =
+        Day02::new("count 2-2 ab and not /x/: abab\n(1-1 a or /^b/) and 3-4 b: bxbb").unwrap();
+    assert_eq!(day.part_one().unwrap(), 2);
+    assert_eq!(
+        day.failures(Part::Two).next(),
+        Some(Failure {
+            line: 2,
+            password: "bxbb",
+            reason: "has 'b' at both 3 and 4".to_string(),
+        })
+    );
 
-        (a == self.policy.c) ^ (b == self.policy.c)
-    }
+    let day =
+        Day02::new("1-2 e\u{301}: e\u{301}te\u{301}\n1-3 🇫🇷: 🇫🇷🇫🇷x\n2-3 ab: x-ab_ab!").unwrap();
+    assert_eq!(day.part_one().unwrap(), 3);
+    assert_eq!(day.part_two().unwrap(), 3);
+    assert!(Day02::new("1-3 a: ab cd").is_err());
 }
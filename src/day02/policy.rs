@@ -0,0 +1,408 @@
+//! Password policies, and the grammar they are written in.
+//!
+//! The puzzle's `1-3 a` is read as [`Count`] in part one and [`Positions`] in
+//! part two. Other rules name themselves, and can be combined:
+//!
+//! ```text
+//! policy := all ("or" all)*
+//! all    := term ("and" term)*
+//! term   := "not" term | "(" policy ")" | "/" pattern "/"
+//!         | "count" N-M needle | "at" N,M needle | N-M needle
+//! ```
+//!
+//! A needle may be several characters long, such as `count 1-2 ab`, and
+//! characters are extended grapheme clusters as Unicode defines them, so `é`
+//! is one whether or not it is written with a combining accent. Patterns are
+//! regular expressions in the syntax of the [`regex`] crate, which matches a
+//! code point at a time.
+
+use std::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag, take_while1},
+    character::complete::{char, space1},
+    combinator::{map, map_res},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, terminated},
+};
+use regex::Regex;
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+use crate::{
+    parsers::{number, IResult},
+    Part,
+};
+
+pub trait PasswordPolicy: fmt::Display {
+    /// Checks `password`, explaining what is wrong with it if it fails.
+    fn check(&self, password: &str) -> Result<(), String>;
+}
+
+pub type Policy<'a> = Box<dyn PasswordPolicy + 'a>;
+
+/// The needle must occur between `min` and `max` times, as whole clusters
+/// that do not overlap.
+#[derive(Debug, Clone)]
+pub struct Count<'a> {
+    pub min: usize,
+    pub max: usize,
+    pub needle: &'a str,
+}
+
+impl PasswordPolicy for Count<'_> {
+    fn check(&self, password: &str) -> Result<(), String> {
+        let mut count = 0;
+        let mut next = 0;
+        for (at, _) in password.grapheme_indices(true) {
+            if at >= next && whole_at(password, at, self.needle) {
+                count += 1;
+                next = at + self.needle.len();
+            }
+        }
+        if (self.min..=self.max).contains(&count) {
+            Ok(())
+        } else {
+            Err(format!(
+                "has {} '{}', needs {} to {}",
+                count, self.needle, self.min, self.max
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Count<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "count {}-{} {}", self.min, self.max, self.needle)
+    }
+}
+
+/// The needle must start at exactly one of two positions, counting clusters
+/// from 1. A position outside the password fails the policy.
+#[derive(Debug, Clone)]
+pub struct Positions<'a> {
+    pub first: usize,
+    pub second: usize,
+    pub needle: &'a str,
+}
+
+impl Positions<'_> {
+    fn at(&self, password: &str, position: usize) -> Result<bool, String> {
+        if position == 0 {
+            return Err("has no position 0, positions count from 1".to_string());
+        }
+        match password.grapheme_indices(true).nth(position - 1) {
+            Some((at, _)) => Ok(whole_at(password, at, self.needle)),
+            None => Err(format!(
+                "has no position {}, only {} characters",
+                position,
+                password.graphemes(true).count()
+            )),
+        }
+    }
+}
+
+impl PasswordPolicy for Positions<'_> {
+    fn check(&self, password: &str) -> Result<(), String> {
+        let (first, second) = (self.first, self.second);
+        match (self.at(password, first)?, self.at(password, second)?) {
+            (true, false) | (false, true) => Ok(()),
+            (true, true) => Err(format!(
+                "has '{}' at both {} and {}",
+                self.needle, first, second
+            )),
+            (false, false) => Err(format!(
+                "has '{}' at neither {} nor {}",
+                self.needle, first, second
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Positions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "at {},{} {}", self.first, self.second, self.needle)
+    }
+}
+
+/// Whether `needle` is a run of whole clusters of `text` starting at `at`,
+/// which must be where a cluster starts. `e` is not found in `é` this way.
+fn whole_at(text: &str, at: usize, needle: &str) -> bool {
+    let end = at + needle.len();
+    text[at..].starts_with(needle)
+        && GraphemeCursor::new(end, text.len(), true)
+            .is_boundary(text, 0)
+            .unwrap_or(false)
+}
+
+/// The password must match a pattern.
+#[derive(Debug, Clone)]
+pub struct Matches(pub Regex);
+
+impl PasswordPolicy for Matches {
+    fn check(&self, password: &str) -> Result<(), String> {
+        if self.0.is_match(password) {
+            Ok(())
+        } else {
+            Err(format!("does not match {}", self))
+        }
+    }
+}
+
+impl fmt::Display for Matches {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "/{}/", self.0.as_str())
+    }
+}
+
+/// Every policy must pass.
+pub struct All<'a>(pub Vec<Policy<'a>>);
+
+impl PasswordPolicy for All<'_> {
+    fn check(&self, password: &str) -> Result<(), String> {
+        let failures: Vec<String> = self
+            .0
+            .iter()
+            .filter_map(|policy| policy.check(password).err())
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures.join("; "))
+        }
+    }
+}
+
+impl fmt::Display for All<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        join(f, &self.0, "and")
+    }
+}
+
+/// At least one policy must pass.
+pub struct Any<'a>(pub Vec<Policy<'a>>);
+
+impl PasswordPolicy for Any<'_> {
+    fn check(&self, password: &str) -> Result<(), String> {
+        let mut failures = vec![];
+        for policy in &self.0 {
+            match policy.check(password) {
+                Ok(()) => return Ok(()),
+                Err(failure) => failures.push(failure),
+            }
+        }
+        Err(format!("fails every option: {}", failures.join("; ")))
+    }
+}
+
+impl fmt::Display for Any<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        join(f, &self.0, "or")
+    }
+}
+
+/// The policy must fail.
+pub struct Not<'a>(pub Policy<'a>);
+
+impl PasswordPolicy for Not<'_> {
+    fn check(&self, password: &str) -> Result<(), String> {
+        match self.0.check(password) {
+            Ok(()) => Err(format!("passes {}", self.0)),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Not<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "not {}", self.0)
+    }
+}
+
+/// Writes `policies` in brackets, separated by `keyword`.
+fn join(f: &mut fmt::Formatter, policies: &[Policy], keyword: &str) -> fmt::Result {
+    f.write_str("(")?;
+    for (i, policy) in policies.iter().enumerate() {
+        if i > 0 {
+            write!(f, " {} ", keyword)?;
+        }
+        write!(f, "{}", policy)?;
+    }
+    f.write_str(")")
+}
+
+fn boxed<'a, P: PasswordPolicy + 'a>(policy: P) -> Policy<'a> {
+    Box::new(policy)
+}
+
+/// Parses a policy, reading the puzzle's `N-M needle` as `part`'s rule.
+pub fn parse_policy<'a>(part: Part) -> impl Fn(&'a str) -> IResult<&'a str, Policy<'a>> {
+    move |input| any(part, input)
+}
+
+fn any(part: Part, input: &str) -> IResult<&str, Policy<'_>> {
+    let (input, mut policies) = separated_list1(keyword("or"), |i| all(part, i))(input)?;
+    let policy = match policies.len() {
+        1 => policies.remove(0),
+        _ => boxed(Any(policies)),
+    };
+    Ok((input, policy))
+}
+
+fn all(part: Part, input: &str) -> IResult<&str, Policy<'_>> {
+    let (input, mut policies) = separated_list1(keyword("and"), |i| term(part, i))(input)?;
+    let policy = match policies.len() {
+        1 => policies.remove(0),
+        _ => boxed(All(policies)),
+    };
+    Ok((input, policy))
+}
+
+fn term(part: Part, input: &str) -> IResult<&str, Policy<'_>> {
+    alt((
+        map(
+            preceded(terminated(tag("not"), space1), |i| term(part, i)),
+            |policy| boxed(Not(policy)),
+        ),
+        delimited(char('('), |i| any(part, i), char(')')),
+        map_res(delimited(char('/'), is_not("/"), char('/')), |source| {
+            Regex::new(source).map(|pattern| boxed(Matches(pattern)))
+        }),
+        map(
+            preceded(terminated(tag("count"), space1), rule('-')),
+            |((min, max), needle)| boxed(Count { min, max, needle }),
+        ),
+        map(
+            preceded(terminated(tag("at"), space1), rule(',')),
+            |((first, second), needle)| {
+                boxed(Positions {
+                    first,
+                    second,
+                    needle,
+                })
+            },
+        ),
+        map(rule('-'), move |((a, b), needle)| match part {
+            Part::One => boxed(Count {
+                min: a,
+                max: b,
+                needle,
+            }),
+            Part::Two => boxed(Positions {
+                first: a,
+                second: b,
+                needle,
+            }),
+        }),
+    ))(input)
+}
+
+/// Two numbers separated by `separator`, then a space and the needle.
+fn rule<'a>(separator: char) -> impl FnMut(&'a str) -> IResult<&'a str, ((usize, usize), &'a str)> {
+    separated_pair(
+        separated_pair(number, char(separator), number),
+        char(' '),
+        take_while1(|c: char| !c.is_whitespace() && !":()/".contains(c)),
+    )
+}
+
+fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    delimited(space1, tag(word), space1)
+}
+
+#[cfg(test)]
+fn parse(part: Part, input: &str) -> Policy<'_> {
+    crate::parsers::all(parse_policy(part), input).unwrap()
+}
+
+#[test]
+fn test_parse_policy() {
+    assert_eq!(parse(Part::One, "1-3 a").to_string(), "count 1-3 a");
+    assert_eq!(parse(Part::Two, "1-3 a").to_string(), "at 1,3 a");
+    let text = "not count 1-2 ab and (/^[a-c]+$/ or at 2,4 x) or 3-4 z";
+    assert_eq!(
+        parse(Part::One, text).to_string(),
+        "((not count 1-2 ab and (/^[a-c]+$/ or at 2,4 x)) or count 3-4 z)"
+    );
+    let policy = parse(Part::Two, text).to_string();
+    assert_eq!(parse(Part::Two, &policy).to_string(), policy);
+
+    let invalid = |input| crate::parsers::all(parse_policy(Part::One), input).is_err();
+    assert!(invalid("1-3"));
+    assert!(invalid("count 1,3 a"));
+    assert!(invalid("/[a/"));
+    assert!(invalid("(1-3 a"));
+    assert!(invalid("1-3 a and"));
+}
+
+#[test]
+fn test_check() {
+    let check = |part, policy, password| parse(part, policy).check(password);
+    assert_eq!(check(Part::One, "1-3 a", "abcde"), Ok(()));
+    assert_eq!(
+        check(Part::One, "1-3 b", "cdefg"),
+        Err("has 0 'b', needs 1 to 3".to_string())
+    );
+    assert_eq!(check(Part::Two, "1-3 a", "abcde"), Ok(()));
+    assert_eq!(
+        check(Part::Two, "1-3 b", "cdefg"),
+        Err("has 'b' at neither 1 nor 3".to_string())
+    );
+    assert_eq!(
+        check(Part::Two, "2-9 c", "ccccccccc"),
+        Err("has 'c' at both 2 and 9".to_string())
+    );
+    assert_eq!(
+        check(Part::Two, "1-9 c", "cc"),
+        Err("has no position 9, only 2 characters".to_string())
+    );
+    assert!(check(Part::Two, "0-1 c", "cc").is_err());
+    assert_eq!(
+        check(Part::Two, "2-2 c", "cc"),
+        Err("has 'c' at both 2 and 2".to_string())
+    );
+
+    assert_eq!(check(Part::Two, "4-5 e\u{301}", "cafe\u{301}x"), Ok(()));
+    assert_eq!(
+        check(Part::One, "1-1 e", "cafe\u{301}"),
+        Err("has 0 'e', needs 1 to 1".to_string())
+    );
+    assert_eq!(check(Part::Two, "1-2 日本", "日本日本"), Ok(()));
+    // a spacing mark, a vowel point, a Hangul syllable written as jamo and
+    // an Arabic vowel mark each join the letter before them
+    assert_eq!(
+        check(Part::Two, "2-3 \u{915}\u{93F}", "x\u{915}\u{93F}y"),
+        Ok(())
+    );
+    assert_eq!(
+        check(Part::One, "1-1 \u{5D1}", "\u{5D1}\u{5B0}"),
+        Err("has 0 '\u{5D1}', needs 1 to 1".to_string())
+    );
+    assert_eq!(
+        check(
+            Part::Two,
+            "2-4 x",
+            "\u{1100}\u{1161}\u{11A8}x\u{628}\u{64E}"
+        ),
+        Err("has no position 4, only 3 characters".to_string())
+    );
+    assert_eq!(
+        check(Part::Two, "2-9 x", "🇬🇧x"),
+        Err("has no position 9, only 2 characters".to_string())
+    );
+
+    assert_eq!(check(Part::One, "count 2-2 ab", "abxab"), Ok(()));
+    assert_eq!(
+        check(Part::One, "at 2,4 ab", "xabab"),
+        Err("has 'ab' at both 2 and 4".to_string())
+    );
+    assert_eq!(
+        check(Part::One, "/^a+$/ and not 1-1 a", "a"),
+        Err("passes count 1-1 a".to_string())
+    );
+    assert_eq!(
+        check(Part::One, "/z/ or 2-3 a", "abc"),
+        Err("fails every option: does not match /z/; has 1 'a', needs 2 to 3".to_string())
+    );
+    assert_eq!(check(Part::One, "/z/ or 1-3 a", "abc"), Ok(()));
+}
@@ -0,0 +1,30 @@
+use crate::{
+    generate::{Generated, Rng},
+    grid::Grid,
+    Part,
+};
+
+const WIDTH: usize = 31;
+const SLOPES: [(usize, usize); 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+/// A map `size` rows tall, about a quarter trees.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let cells = (0..WIDTH * size.max(1)).map(|_| rng.chance(1, 4)).collect();
+    let forest = Grid::new(WIDTH, cells);
+
+    let trees = |(right, down): (usize, usize)| {
+        (0..forest.height())
+            .step_by(down)
+            .enumerate()
+            .filter(|&(i, y)| *forest.wrapping((i * right, y)))
+            .count()
+    };
+
+    let input = forest.display(|&tree| if tree { '#' } else { '.' });
+    Generated::new(input.to_string())
+        .answer(Part::One, trees((3, 1)))
+        .answer(
+            Part::Two,
+            SLOPES.iter().map(|&s| trees(s)).product::<usize>(),
+        )
+}
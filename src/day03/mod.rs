@@ -1,106 +1,188 @@
-use crate::Challenge;
-
-use nom::{
-    branch::alt,
-    character::complete::{char, line_ending},
-    multi::many1,
-    multi::separated_list1,
-    IResult,
+use serde::Deserialize;
+
+use crate::{
+    answer::Answer,
+    config::Overrides,
+    error::{Error, Result},
+    generate::{Generated, Rng},
+    grid::{Direction, Grid, Point},
+    parsers::{all, from_char, IResult},
+    Challenge,
 };
 
+use path::{path, Edge};
+use search::Search;
+
+mod generate;
+mod path;
+mod search;
+
 pub struct Day03 {
-    grid: Grid,
+    grid: Grid<Spot>,
+    params: Params,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// The slope part one counts trees along, as (right, down). Either may be
+    /// negative, to go left or up.
+    pub slope: Direction,
+    /// The slopes part two multiplies the tree counts of.
+    pub slopes: Vec<Direction>,
+    /// Where every path starts, as (x, y).
+    pub start: Point,
+    /// What paths do at the edges of the map.
+    pub edge: Edge,
+    /// Slopes to rank in part two, instead of multiplying the tree counts.
+    pub search: Option<Search>,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            slope: (3, 1),
+            slopes: vec![(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)],
+            start: (0, 0),
+            edge: Edge::WrapHorizontal,
+            search: None,
+        }
+    }
 }
 
-impl Challenge for Day03 {
+impl Challenge<'_> for Day03 {
     fn name() -> &'static str {
         "day03"
     }
-    fn new(input: String) -> Self{
-        Day03 {
-            grid: parse_rows(&input).unwrap().1,
+    fn day() -> u8 {
+        3
+    }
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day03 {
+            grid: all(Grid::parser(parse_spot), input)?,
+            params: Params::default(),
+        })
+    }
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        let params: Params = overrides.apply()?;
+        if self.grid.get(params.start).is_none() {
+            return Err(Error::Params(format!(
+                "start {:?} is off the map",
+                params.start
+            )));
+        }
+        if let Some(search) = &params.search {
+            search.validate()?;
         }
+        self.params = params;
+        Ok(())
     }
-    fn part_one(&self) -> usize {
-        self.grid.iter((3, 1)).filter(|&spot| spot == Spot::Tree).count()
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_two(&self) -> usize {
-        let trees11 = self.grid.iter((1, 1)).filter(|&spot| spot == Spot::Tree).count();
-        let trees31 = self.grid.iter((3, 1)).filter(|&spot| spot == Spot::Tree).count();
-        let trees51 = self.grid.iter((5, 1)).filter(|&spot| spot == Spot::Tree).count();
-        let trees71 = self.grid.iter((7, 1)).filter(|&spot| spot == Spot::Tree).count();
-        let trees12 = self.grid.iter((1, 2)).filter(|&spot| spot == Spot::Tree).count();
-        trees11 * trees31 * trees51 * trees71 * trees12
+    fn part_one(&self) -> Result<Answer> {
+        Ok(self.trees(self.params.slope).into())
+    }
+    fn part_two(&self) -> Result<Answer> {
+        if let Some(search) = &self.params.search {
+            return Ok(self.search(search).into());
+        }
+        let trees = self.trees_each(&self.params.slopes);
+        Ok(trees.iter().product::<usize>().into())
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum Spot {
-    Empty,
-    Tree,
-}
+impl Day03 {
+    fn path(&self, slope: Direction) -> impl Iterator<Item = Point> {
+        let size = (self.grid.width(), self.grid.height());
+        path(self.params.start, slope, self.params.edge, size)
+    }
 
-fn parse_spot(input: &str) -> IResult<&str, Spot> {
-    let (input, c) = alt((char('.'), char('#')))(input)?;
-    if c == '.' {
-        Ok((input, Spot::Empty))
-    } else {
-        Ok((input, Spot::Tree))
+    /// The number of trees met going along `slope`.
+    fn trees(&self, slope: Direction) -> usize {
+        self.path(slope)
+            .filter(|&point| self.grid[point] == Spot::Tree)
+            .count()
+    }
+
+    /// The number of trees met going along each of `slopes`, found by working
+    /// out every path first and then reading the map once, row by row.
+    fn trees_each(&self, slopes: &[Direction]) -> Vec<usize> {
+        let mut visits = vec![vec![]; self.grid.height()];
+        for (i, &slope) in slopes.iter().enumerate() {
+            for (x, y) in self.path(slope) {
+                visits[y].push((x, i));
+            }
+        }
+        let mut trees = vec![0; slopes.len()];
+        for (row, visits) in self.grid.rows().zip(visits) {
+            for (x, i) in visits {
+                if row[x] == Spot::Tree {
+                    trees[i] += 1;
+                }
+            }
+        }
+        trees
     }
-}
 
-#[derive(Debug)]
-struct Row(Vec<Spot>);
-#[derive(Debug)]
-struct Grid(Vec<Row>);
+    /// Ranks the slopes of `search` and shows the path of the best. Slopes
+    /// that take the same path are only followed once, and all in one pass.
+    fn search(&self, search: &Search) -> Vec<String> {
+        let size = (self.grid.width(), self.grid.height());
+        let canonical = |slope| self.params.edge.canonical(slope, size);
+        let mut distinct: Vec<Direction> = search.slopes().map(canonical).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        let trees = self.trees_each(&distinct);
 
-fn parse_row(input: &str) -> IResult<&str, Row> {
-    let (input, spots) = many1(parse_spot)(input)?;
-    Ok((input, Row(spots)))
-}
+        let mut counts: Vec<(Direction, usize)> = search
+            .slopes()
+            .map(|slope| {
+                let i = distinct.binary_search(&canonical(slope)).unwrap();
+                (slope, trees[i])
+            })
+            .collect();
+        search.rank(&mut counts);
 
-fn parse_rows(input: &str) -> IResult<&str, Grid> {
-    let (input, rows) = separated_list1(line_ending, parse_row)(input)?;
-    Ok((input, Grid(rows)))
-}
+        let mut lines = search.summary(&counts);
+        lines.push(String::new());
+        let path = self.show_path(counts[0].0);
+        lines.extend(path.lines().map(str::to_string));
+        lines
+    }
 
-impl Grid {
-    fn iter(&self, step: (usize, usize)) -> GridIter {
-        GridIter {
-            grid: &self,
-            pos: (0, 0),
-            step,
-            width: self.0[0].0.len(),
+    /// The map with the cells along `slope` marked, `X` where there is a
+    /// tree and `O` where there is not, as the puzzle draws them.
+    fn show_path(&self, slope: Direction) -> String {
+        let cells = self.grid.iter().map(|(_, &spot)| (spot, false)).collect();
+        let mut marked = Grid::new(self.grid.width(), cells);
+        for point in self.path(slope) {
+            marked[point].1 = true;
         }
+        let draw = |&(spot, visited): &(Spot, bool)| match (spot, visited) {
+            (Spot::Tree, true) => 'X',
+            (Spot::Empty, true) => 'O',
+            (Spot::Tree, false) => '#',
+            (Spot::Empty, false) => '.',
+        };
+        marked.display(draw).to_string()
     }
 }
 
-struct GridIter<'a> {
-    grid: &'a Grid,
-    pos: (usize, usize),
-    step: (usize, usize),
-    width: usize,
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Spot {
+    Empty,
+    Tree,
 }
 
-impl<'a> Iterator for GridIter<'a> {
-    type Item = Spot;
-    fn next(&mut self) -> Option<Self::Item> {
-        let (x, y) = self.pos;
-
-        if y >= self.grid.0.len() {
-            None
-        } else {
-            let current = self.grid.0[y].0[x];
-            let (sx, sy) = self.step;
-            self.pos = ((x + sx) % self.width, y + sy);
-            Some(current)
-        }
-    }
+fn parse_spot(input: &str) -> IResult<&str, Spot> {
+    from_char(&[('.', Spot::Empty), ('#', Spot::Tree)])(input)
 }
 
 #[test]
-fn test_iter() {
-    let (left, grid) = parse_rows(
+fn test_trees() {
+    let grid = all(
+        Grid::parser(parse_spot),
         "..##.......
 #...#...#..
 .#....#..#.
@@ -114,19 +196,43 @@ fn test_iter() {
 .#..#...#.#",
     )
     .unwrap();
+    let day = Day03 {
+        grid,
+        params: Params::default(),
+    };
 
-    assert_eq!(left.len(), 0);
+    assert_eq!(day.trees((1, 1)), 2);
+    assert_eq!(day.trees((3, 1)), 7);
+    assert_eq!(day.trees((5, 1)), 3);
+    assert_eq!(day.trees((7, 1)), 4);
+    assert_eq!(day.trees((1, 2)), 2);
+    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+    assert_eq!(day.trees_each(&slopes), vec![2, 7, 3, 4, 2]);
 
-    let spots: Vec<Spot> = grid.iter((3, 1)).collect();
-    use Spot::*;
-    assert_eq!(
-        spots,
-        vec![Empty, Empty, Tree, Empty, Tree, Tree, Empty, Tree, Tree, Tree, Tree]
-    );
+    let mut day = day;
+    let slopes = [(1, 1), (-3, 1), (2, -1), (0, 3), (4, 0), (-7, -2), (0, 0)];
+    for &edge in &[Edge::WrapHorizontal, Edge::Wrap, Edge::Stop, Edge::Reflect] {
+        day.params.edge = edge;
+        day.params.start = (5, 4);
+        let each: Vec<usize> = slopes.iter().map(|&slope| day.trees(slope)).collect();
+        assert_eq!(day.trees_each(&slopes), each);
+    }
+    day.params.edge = Edge::Stop;
+    day.params.start = (10, 10);
+    assert_eq!(day.trees((-1, -1)), 2);
+
+    day.configure(&"edge = \"reflect\"\nstart = [10, 0]".parse().unwrap())
+        .unwrap();
+    assert_eq!(day.params.edge, Edge::Reflect);
+    assert!(day.configure(&"start = [11, 0]".parse().unwrap()).is_err());
+    assert!(day
+        .configure(&"edge = \"bounce\"".parse().unwrap())
+        .is_err());
 }
+
 #[test]
-fn test_count() {
-    let (left, grid) = parse_rows(
+fn test_search() {
+    let mut day = Day03::new(
         "..##.......
 #...#...#..
 .#....#..#.
@@ -140,19 +246,25 @@ fn test_count() {
 .#..#...#.#",
     )
     .unwrap();
+    let overrides = "search = { goal = \"most\", right = [-8, 3], down = [1, 1], top = 3 }";
+    day.configure(&overrides.parse().unwrap()).unwrap();
+    let lines = match day.part_two().unwrap() {
+        Answer::Lines(lines) => lines,
+        answer => panic!("expected lines, got {}", answer),
+    };
+    assert_eq!(
+        lines[..6],
+        [
+            "most trees: 7, going (-8, 1) or (3, 1)",
+            "  1. (-8, 1): 7",
+            "  2. (3, 1): 7",
+            "  3. (-1, 1): 5",
+            "",
+            "O.##.......",
+        ]
+    );
+    assert_eq!(lines[6..8], ["#..O#...#..", ".#....X..#."]);
 
-    assert_eq!(left.len(), 0);
-
-    let trees11 = grid.iter((1, 1)).filter(|&spot| spot == Spot::Tree).count();
-    let trees31 = grid.iter((3, 1)).filter(|&spot| spot == Spot::Tree).count();
-    let trees51 = grid.iter((5, 1)).filter(|&spot| spot == Spot::Tree).count();
-    let trees71 = grid.iter((7, 1)).filter(|&spot| spot == Spot::Tree).count();
-    let trees12 = grid.iter((1, 2)).filter(|&spot| spot == Spot::Tree).count();
-
-    assert_eq!(trees11, 2);
-    assert_eq!(trees31, 7);
-    assert_eq!(trees51, 3);
-    assert_eq!(trees71, 4);
-    assert_eq!(trees12, 2);
+    let overrides = "search = { right = [1, 0] }";
+    assert!(day.configure(&overrides.parse().unwrap()).is_err());
 }
-
@@ -0,0 +1,202 @@
+//! Paths down day 3's map along a slope, and what they do at its edges.
+
+use serde::Deserialize;
+
+use crate::grid::{Direction, Point};
+
+/// What a path does when it reaches an edge of the map. A path that never
+/// leaves the map ends just before it would go round again.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Edge {
+    /// The map repeats to the left and right, and the path ends when it
+    /// leaves the top or bottom, as in the puzzle.
+    WrapHorizontal,
+    /// The map repeats in every direction.
+    Wrap,
+    /// The path ends when it leaves the map.
+    Stop,
+    /// The path bounces off every edge.
+    Reflect,
+}
+
+impl Edge {
+    fn axes(self) -> (Axis, Axis) {
+        match self {
+            Edge::WrapHorizontal => (Axis::Wrap, Axis::Stop),
+            Edge::Wrap => (Axis::Wrap, Axis::Wrap),
+            Edge::Stop => (Axis::Stop, Axis::Stop),
+            Edge::Reflect => (Axis::Reflect, Axis::Reflect),
+        }
+    }
+
+    /// The slope that takes the same path as `slope` on a map of `size` with
+    /// the smallest non-negative steps along axes that wrap or reflect, since
+    /// a whole trip round such an axis ends where it began.
+    pub fn canonical(self, (right, down): Direction, (width, height): (usize, usize)) -> Direction {
+        let (x_axis, y_axis) = self.axes();
+        (
+            x_axis.canonical(right, width),
+            y_axis.canonical(down, height),
+        )
+    }
+}
+
+/// What a path does at the ends of one axis.
+#[derive(Debug, Copy, Clone)]
+enum Axis {
+    Wrap,
+    Stop,
+    Reflect,
+}
+
+/// How many steps a path takes along one axis before it must end.
+enum Extent {
+    /// It leaves the map after this many cells.
+    Leaves(usize),
+    /// It is back where it started, going the same way, after this many.
+    Repeats(usize),
+}
+
+impl Axis {
+    /// The coordinate after `steps` steps of `delta` from `start`, along an
+    /// axis `len` cells long.
+    fn at(self, start: usize, delta: isize, steps: usize, len: usize) -> usize {
+        let unfolded = start as i128 + steps as i128 * delta as i128;
+        let len = len as i128;
+        let folded = match self {
+            Axis::Stop => unfolded,
+            Axis::Wrap => unfolded.rem_euclid(len),
+            // there and back again is one period of 2 * (len - 1) cells
+            Axis::Reflect => match 2 * (len - 1) {
+                0 => 0,
+                period => {
+                    let i = unfolded.rem_euclid(period);
+                    i.min(period - i)
+                }
+            },
+        };
+        folded as usize
+    }
+
+    fn canonical(self, delta: isize, len: usize) -> isize {
+        match self {
+            Axis::Stop => delta,
+            Axis::Wrap => delta.rem_euclid(len as isize),
+            Axis::Reflect => match 2 * (len - 1) {
+                0 => 0,
+                period => delta.rem_euclid(period as isize),
+            },
+        }
+    }
+
+    fn extent(self, start: usize, delta: isize, len: usize) -> Extent {
+        let step = delta.unsigned_abs();
+        match self {
+            Axis::Stop if delta > 0 => Extent::Leaves((len - 1 - start) / step + 1),
+            Axis::Stop if delta < 0 => Extent::Leaves(start / step + 1),
+            Axis::Stop => Extent::Repeats(1),
+            Axis::Wrap => Extent::Repeats(len / gcd(len, step % len)),
+            Axis::Reflect => match 2 * (len - 1) {
+                0 => Extent::Repeats(1),
+                period => Extent::Repeats(period / gcd(period, step % period)),
+            },
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// How many cells a path visits before it leaves the map or would go round
+/// again, counting `start`.
+fn steps(start: Point, slope: Direction, edge: Edge, (width, height): (usize, usize)) -> usize {
+    if start.0 >= width || start.1 >= height {
+        return 0;
+    }
+    let (x_axis, y_axis) = edge.axes();
+    let extents = [
+        x_axis.extent(start.0, slope.0, width),
+        y_axis.extent(start.1, slope.1, height),
+    ];
+    let leaves = extents.iter().filter_map(|extent| match extent {
+        Extent::Leaves(steps) => Some(*steps),
+        Extent::Repeats(_) => None,
+    });
+    let repeats = extents.iter().map(|extent| match extent {
+        Extent::Leaves(_) => 1,
+        Extent::Repeats(steps) => *steps,
+    });
+    match leaves.min() {
+        Some(steps) => steps,
+        None => repeats.fold(1, |a, b| a / gcd(a, b) * b),
+    }
+}
+
+/// The points visited going `slope` from `start`, included, on a map of
+/// `size` (width, height). A path from off the map visits nothing.
+pub fn path(
+    start: Point,
+    slope: Direction,
+    edge: Edge,
+    size: (usize, usize),
+) -> impl Iterator<Item = Point> {
+    let (x_axis, y_axis) = edge.axes();
+    (0..steps(start, slope, edge, size)).map(move |i| {
+        (
+            x_axis.at(start.0, slope.0, i, size.0),
+            y_axis.at(start.1, slope.1, i, size.1),
+        )
+    })
+}
+
+#[test]
+fn test_path() {
+    let path = |start, slope, edge| path(start, slope, edge, (4, 3)).collect::<Vec<_>>();
+    assert_eq!(
+        path((0, 0), (3, 1), Edge::WrapHorizontal),
+        vec![(0, 0), (3, 1), (2, 2)]
+    );
+    assert_eq!(
+        path((2, 2), (-3, -1), Edge::WrapHorizontal),
+        vec![(2, 2), (3, 1), (0, 0)]
+    );
+    assert_eq!(path((0, 0), (1, 0), Edge::WrapHorizontal).len(), 4);
+    assert_eq!(path((1, 1), (1, 1), Edge::Stop), vec![(1, 1), (2, 2)]);
+    assert_eq!(path((1, 1), (0, 0), Edge::Stop), vec![(1, 1)]);
+    assert_eq!(path((4, 0), (1, 1), Edge::Stop), vec![]);
+    assert_eq!(path((0, 0), (1, 1), Edge::Wrap).len(), 12);
+    assert_eq!(path((0, 0), (2, 0), Edge::Wrap), vec![(0, 0), (2, 0)]);
+    let reflected = path((0, 0), (1, 1), Edge::Reflect);
+    assert_eq!(reflected.len(), 12);
+    assert_eq!(
+        reflected[..6],
+        [(0, 0), (1, 1), (2, 2), (3, 1), (2, 0), (1, 1)]
+    );
+    assert_eq!(
+        path((3, 0), (-5, 2), Edge::Reflect)[..4],
+        [(3, 0), (2, 2), (1, 0), (0, 2)]
+    );
+}
+
+#[test]
+fn test_canonical() {
+    let size = (4, 3);
+    let edges = [Edge::WrapHorizontal, Edge::Wrap, Edge::Stop, Edge::Reflect];
+    for &edge in &edges {
+        for &slope in &[(5, 1), (-3, -4), (7, 2), (0, -1)] {
+            let canonical = edge.canonical(slope, size);
+            let path = |slope| path((1, 1), slope, edge, size).collect::<Vec<_>>();
+            assert_eq!(path(canonical), path(slope), "{:?} {:?}", edge, slope);
+        }
+    }
+    assert_eq!(Edge::WrapHorizontal.canonical((-3, -4), size), (1, -4));
+    assert_eq!(Edge::Wrap.canonical((-3, -4), size), (1, 2));
+    assert_eq!(Edge::Stop.canonical((-3, -4), size), (-3, -4));
+    assert_eq!(Edge::Reflect.canonical((7, -4), size), (1, 0));
+}
@@ -0,0 +1,173 @@
+//! Searching a range of slopes for the one that meets the fewest or the most
+//! trees, instead of multiplying the counts of a fixed few.
+
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    grid::Direction,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Goal {
+    Fewest,
+    Most,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Search {
+    /// The smallest and largest steps right to try.
+    pub right: (isize, isize),
+    /// The smallest and largest steps down to try.
+    pub down: (isize, isize),
+    pub goal: Goal,
+    /// How many of the best slopes to list.
+    pub top: usize,
+}
+
+impl Default for Search {
+    fn default() -> Self {
+        Search {
+            right: (0, 10),
+            down: (1, 3),
+            goal: Goal::Fewest,
+            top: 5,
+        }
+    }
+}
+
+/// The most slopes a search may try, since each walks the whole map.
+const MAX_SLOPES: u128 = 10_000;
+
+/// How many steps `range` spans, counting both ends.
+fn span((first, last): (isize, isize)) -> u128 {
+    (last as i128 - first as i128 + 1) as u128
+}
+
+impl Search {
+    pub fn validate(&self) -> Result<()> {
+        if self.right.0 > self.right.1 || self.down.0 > self.down.1 {
+            return Err(Error::Params("search ranges must not be empty".to_string()));
+        }
+        let slopes = span(self.right) * span(self.down);
+        if slopes > MAX_SLOPES {
+            return Err(Error::Params(format!(
+                "search would try {} slopes, more than the {} allowed",
+                slopes, MAX_SLOPES
+            )));
+        }
+        if self.top == 0 {
+            return Err(Error::Params(
+                "search must list at least one slope".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Every slope in the search, down by down.
+    pub fn slopes(&self) -> impl Iterator<Item = Direction> + '_ {
+        (self.down.0..=self.down.1)
+            .flat_map(move |down| (self.right.0..=self.right.1).map(move |right| (right, down)))
+    }
+
+    /// Sorts `counts` of trees per slope best first, keeping ties in order.
+    pub fn rank(&self, counts: &mut [(Direction, usize)]) {
+        match self.goal {
+            Goal::Fewest => counts.sort_by_key(|&(_, trees)| trees),
+            Goal::Most => counts.sort_by_key(|&(_, trees)| std::cmp::Reverse(trees)),
+        }
+    }
+
+    /// The headline and the top of the ranking, from `ranked` best first.
+    pub fn summary(&self, ranked: &[(Direction, usize)]) -> Vec<String> {
+        let best = ranked[0].1;
+        let winners: Vec<String> = ranked
+            .iter()
+            .take_while(|&&(_, trees)| trees == best)
+            .map(|(slope, _)| format!("{:?}", slope))
+            .collect();
+        let goal = match self.goal {
+            Goal::Fewest => "fewest",
+            Goal::Most => "most",
+        };
+        let mut lines = vec![format!(
+            "{} trees: {}, going {}",
+            goal,
+            best,
+            winners.join(" or ")
+        )];
+        lines.extend(
+            ranked
+                .iter()
+                .take(self.top)
+                .enumerate()
+                .map(|(i, (slope, trees))| format!("{:>3}. {:?}: {}", i + 1, slope, trees)),
+        );
+        lines
+    }
+}
+
+#[test]
+fn test_search() {
+    let search = Search {
+        right: (-1, 1),
+        down: (1, 2),
+        goal: Goal::Most,
+        top: 2,
+    };
+    assert!(search.validate().is_ok());
+    assert_eq!(
+        search.slopes().collect::<Vec<_>>(),
+        vec![(-1, 1), (0, 1), (1, 1), (-1, 2), (0, 2), (1, 2)]
+    );
+
+    let mut counts = vec![((0, 1), 3), ((1, 1), 5), ((0, 2), 1), ((1, 2), 5)];
+    search.rank(&mut counts);
+    assert_eq!(
+        counts,
+        vec![((1, 1), 5), ((1, 2), 5), ((0, 1), 3), ((0, 2), 1)]
+    );
+    assert_eq!(
+        search.summary(&counts),
+        vec![
+            "most trees: 5, going (1, 1) or (1, 2)",
+            "  1. (1, 1): 5",
+            "  2. (1, 2): 5",
+        ]
+    );
+
+    assert!(Search {
+        down: (2, 1),
+        ..Search::default()
+    }
+    .validate()
+    .is_err());
+    assert!(Search {
+        right: (0, 99),
+        down: (1, 100),
+        ..Search::default()
+    }
+    .validate()
+    .is_ok());
+    assert_eq!(
+        Search {
+            right: (isize::MIN, isize::MAX),
+            ..Search::default()
+        }
+        .validate()
+        .unwrap_err()
+        .to_string(),
+        format!(
+            "bad parameters: search would try {} slopes, more than the 10000 allowed",
+            3 * (1u128 << usize::BITS)
+        )
+    );
+    assert!(Search {
+        top: 0,
+        ..Search::default()
+    }
+    .validate()
+    .is_err());
+}
@@ -0,0 +1,95 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+const REQUIRED: [&str; 7] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
+const EYE_COLOURS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+fn hex(rng: &mut Rng, digits: usize) -> String {
+    (0..digits)
+        .map(|_| *rng.pick(b"0123456789abcdef") as char)
+        .collect()
+}
+
+fn digits(rng: &mut Rng, digits: usize) -> String {
+    (0..digits)
+        .map(|_| (b'0' + rng.range(0..10) as u8) as char)
+        .collect()
+}
+
+/// A value for `field` that passes its validation rule.
+fn valid(rng: &mut Rng, field: &str) -> String {
+    match field {
+        "byr" => rng.range(1920..2003).to_string(),
+        "iyr" => rng.range(2010..2021).to_string(),
+        "eyr" => rng.range(2020..2031).to_string(),
+        "hgt" if rng.chance(1, 2) => format!("{}cm", rng.range(150..194)),
+        "hgt" => format!("{}in", rng.range(59..77)),
+        "hcl" => format!("#{}", hex(rng, 6)),
+        "ecl" => rng.pick(&EYE_COLOURS).to_string(),
+        "pid" => digits(rng, 9),
+        _ => rng.range(100..1000).to_string(),
+    }
+}
+
+/// A value for `field` that fails its validation rule.
+fn invalid(rng: &mut Rng, field: &str) -> String {
+    match field {
+        "byr" => rng.pick(&[1900, 1919, 2003, 2020]).to_string(),
+        "iyr" => rng.pick(&[2000, 2009, 2021, 2030]).to_string(),
+        "eyr" => rng.pick(&[2010, 2019, 2031, 2040]).to_string(),
+        "hgt" => rng
+            .pick(&["149cm", "194cm", "58in", "77in", "170", "70ft"])
+            .to_string(),
+        "hcl" => rng.pick(&["123abc", "#12345", "#abcdeg", "z"]).to_string(),
+        "ecl" => rng.pick(&["xyz", "red", "#123abc", "1"]).to_string(),
+        _ => {
+            let len = *rng.pick(&[5, 8, 10]);
+            digits(rng, len)
+        }
+    }
+}
+
+/// Passports that are each either missing a required field, complete but with
+/// one invalid value, or entirely valid.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let (mut complete, mut valid_count) = (0, 0);
+    let mut passports = vec![];
+    for _ in 0..size.max(1) {
+        let mut fields: Vec<(&str, String)> = REQUIRED
+            .iter()
+            .map(|&field| (field, valid(rng, field)))
+            .collect();
+        if rng.chance(1, 2) {
+            fields.push(("cid", valid(rng, "cid")));
+        }
+        match rng.range(0..3) {
+            0 => {
+                fields.remove(rng.range(0..REQUIRED.len()));
+            }
+            1 => {
+                let i = rng.range(0..REQUIRED.len());
+                fields[i].1 = invalid(rng, fields[i].0);
+                complete += 1;
+            }
+            _ => {
+                complete += 1;
+                valid_count += 1;
+            }
+        }
+        rng.shuffle(&mut fields);
+
+        let mut passport = String::new();
+        for (i, (field, value)) in fields.iter().enumerate() {
+            if i > 0 {
+                passport.push(if rng.chance(1, 4) { '\n' } else { ' ' });
+            }
+            passport += &format!("{}:{}", field, value);
+        }
+        passports.push(passport);
+    }
+    Generated::new(passports.join("\n\n"))
+        .answer(Part::One, complete)
+        .answer(Part::Two, valid_count)
+}
@@ -1,93 +1,118 @@
-use crate::Challenge;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    answer::Answer,
+    config::Overrides,
+    error::Result,
+    generate::{Generated, Rng},
+    parsers::all,
+    Challenge, Solver,
+};
+use report::Report;
+use schema::Schema;
+
+mod generate;
 mod parse;
+mod report;
+mod schema;
+
+pub struct Day04<'a> {
+    passports: Vec<Passport<'a>>,
+    /// The schema passports are checked against, when not the puzzle's.
+    schema: Option<Schema>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// A schema to check passports against instead of the puzzle's rules:
+    /// `puzzle` or `strict` for a built-in one, or a TOML file as described in
+    /// [`schema`]. A relative path is taken from the config file that sets
+    /// it, or from the current directory.
+    pub schema: Option<PathBuf>,
+}
+
+impl Day04<'_> {
+    fn schema(&self) -> &Schema {
+        self.schema.as_ref().unwrap_or_else(|| Schema::puzzle())
+    }
+
+    /// What is wrong with each passport, if anything.
+    fn reports(&self) -> impl Iterator<Item = Report<'_>> {
+        let schema = self.schema();
+        self.passports
+            .iter()
+            .enumerate()
+            .map(move |(i, passport)| Report::new(i + 1, passport, schema))
+    }
+}
 
-pub struct Day04 {
-    passports: Vec<Passport>,
+impl Solver for Day04<'static> {
+    type For<'a> = Day04<'a>;
 }
 
-impl Challenge for Day04 {
+impl<'a> Challenge<'a> for Day04<'a> {
     fn name() -> &'static str {
         "day04"
     }
-    fn new(input: String) -> Self {
-        Day04 {
-            passports: parse::passports(&input).unwrap().1,
-        }
+    fn day() -> u8 {
+        4
     }
-    fn part_one(&self) -> usize {
-        self.passports
+    fn new(input: &'a str) -> Result<Self> {
+        Ok(Day04 {
+            passports: all(parse::passports, input)?,
+            schema: None,
+        })
+    }
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        let params: Params = overrides.apply()?;
+        self.schema = match params.schema {
+            Some(path) => Some(match path.to_str().and_then(Schema::builtin) {
+                Some(schema) => schema.clone(),
+                None => Schema::load(overrides.path("schema", &path))?,
+            }),
+            None => None,
+        };
+        Ok(())
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+    fn part_one(&self) -> Result<Answer> {
+        Ok(self
+            .passports
             .iter()
-            .filter(|p| p.has_correct_fields())
+            .filter(|p| p.has_required_fields(self.schema()))
             .count()
+            .into())
     }
-    fn part_two(&self) -> usize {
-        self.passports.iter().filter(|p| p.is_valid()).count()
+    fn part_two(&self) -> Result<Answer> {
+        Ok(self.reports().filter(|report| report.valid).count().into())
+    }
+    fn report(&self) -> Option<Value> {
+        let reports: Vec<Report> = self.reports().collect();
+        Some(report::json(&reports))
     }
-}
-
-#[derive(Debug, PartialEq, Copy, Clone)]
-#[repr(u8)]
-pub enum Field {
-    BirthYear = 0x01,
-    IssueYear = 0x02,
-    ExpirationYear = 0x04,
-    Height = 0x08,
-    HairColor = 0x10,
-    EyeColor = 0x20,
-    PassportID = 0x40,
-    CountryID = 0x80,
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct FieldData {
-    field: Field,
-    data: String,
+pub struct FieldData<'a> {
+    key: &'a str,
+    data: &'a str,
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct Passport(Vec<FieldData>);
-
-impl FieldData {
-    fn is_valid(&self) -> bool {
-        use nom::combinator::{complete, recognize};
-        use parse::{eye_colour, height, hex_colour, number, Height::*};
-        match self.field {
-            Field::BirthYear => {
-                complete(number)(&self.data).map_or(false, |(_, year)| 1920 <= year && year <= 2002)
-            }
-            Field::IssueYear => {
-                complete(number)(&self.data).map_or(false, |(_, year)| 2010 <= year && year <= 2020)
-            }
-            Field::ExpirationYear => {
-                complete(number)(&self.data).map_or(false, |(_, year)| 2020 <= year && year <= 2030)
-            }
-            Field::Height => {
-                complete(height)(&self.data).map_or(false, |(_, height)| match height {
-                    Centimetres(cm) => 150 <= cm && cm <= 193,
-                    Inches(cm) => 59 <= cm && cm <= 76,
-                })
-            }
-            Field::HairColor => complete(hex_colour)(&self.data).is_ok(),
-            Field::EyeColor => complete(eye_colour)(&self.data).is_ok(),
-            Field::PassportID => {
-                recognize(complete(number))(&self.data).map_or(false, |(_, input)| input.len() == 9)
-            }
-            Field::CountryID => true,
-        }
-    }
-}
+pub struct Passport<'a>(Vec<FieldData<'a>>);
 
-impl Passport {
-    fn into_bits(&self) -> u8 {
-        self.0.iter().fold(0, |a, fd| a | fd.field as u8)
-    }
-    fn has_correct_fields(&self) -> bool {
-        let bits = self.into_bits();
-        const MASK: u8 = 0x7f;
-        bits & MASK == MASK
+impl Passport<'_> {
+    fn has(&self, key: &str) -> bool {
+        self.0.iter().any(|field| field.key == key)
     }
-    fn is_valid(&self) -> bool {
-        self.has_correct_fields() && self.0.iter().fold(true, |a, fd| a && fd.is_valid())
+    fn has_required_fields(&self, schema: &Schema) -> bool {
+        schema.required().all(|key| self.has(key))
     }
 }
 
@@ -111,9 +136,17 @@ iyr:2011 ecl:brn hgt:59in";
     assert_eq!(input.len(), 0);
     assert_eq!(passports.len(), 4);
 
-    let valid_passports: Vec<bool> = passports.iter().map(Passport::has_correct_fields).collect();
+    let schema = Schema::puzzle();
+    let valid_passports: Vec<bool> = passports
+        .iter()
+        .map(|p| p.has_required_fields(schema))
+        .collect();
     assert_eq!(valid_passports, vec![true, false, true, false]);
 
-    let valid_passports: Vec<u8> = passports.iter().map(Passport::into_bits).collect();
-    assert_eq!(valid_passports, vec![0xff, 0xf7, 0x7f, 0x7e]);
+    let strict = Schema::builtin("strict").unwrap();
+    let valid_passports: Vec<bool> = passports
+        .iter()
+        .map(|p| p.has_required_fields(strict))
+        .collect();
+    assert_eq!(valid_passports, vec![true, false, false, false]);
 }
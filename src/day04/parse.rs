@@ -1,106 +1,42 @@
-use super::{Field, FieldData, Passport};
+use super::{FieldData, Passport};
 
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{char, digit1, line_ending, one_of},
-    combinator::{map_res, value},
-    multi::{count, separated_list1},
-    sequence::separated_pair,
-    IResult, InputTakeAtPosition,
-};
+use crate::parsers::{groups, key_value, record, IResult};
+
+use nom::{bytes::complete::take_while1, error::context, InputTakeAtPosition};
 
 pub fn data(input: &str) -> IResult<&str, &str> {
     input.split_at_position_complete(|item| item.is_ascii_whitespace())
 }
 
-pub fn field(input: &str) -> IResult<&str, Field> {
-    alt((
-        value(Field::BirthYear, tag("byr")),
-        value(Field::IssueYear, tag("iyr")),
-        value(Field::ExpirationYear, tag("eyr")),
-        value(Field::Height, tag("hgt")),
-        value(Field::HairColor, tag("hcl")),
-        value(Field::EyeColor, tag("ecl")),
-        value(Field::PassportID, tag("pid")),
-        value(Field::CountryID, tag("cid")),
-    ))(input)
+/// A field's name, which the schema decides the meaning of.
+pub fn key(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_' || c == '-')(input)
 }
 
-pub fn field_data(input: &str) -> IResult<&str, FieldData> {
-    let (input, (field, data)) = separated_pair(field, char(':'), data)(input)?;
-    Ok((
-        input,
-        FieldData {
-            field,
-            data: data.to_string(),
-        },
-    ))
+pub fn field_data(input: &str) -> IResult<&str, FieldData<'_>> {
+    let (input, (key, data)) = context("field", key_value(key, data))(input)?;
+    Ok((input, FieldData { key, data }))
 }
 
-pub fn passport(input: &str) -> IResult<&str, Passport> {
-    let (input, fields) = separated_list1(one_of("\n\r\t "), field_data)(input)?;
+pub fn passport(input: &str) -> IResult<&str, Passport<'_>> {
+    let (input, fields) = record(field_data)(input)?;
     Ok((input, Passport(fields)))
 }
 
-pub fn passports(input: &str) -> IResult<&str, Vec<Passport>> {
-    separated_list1(count(line_ending, 2), passport)(input)
-}
-
-pub fn hex_colour(input: &str) -> IResult<&str, Vec<char>> {
-    let (input, _) = char('#')(input)?;
-    count(one_of("0123456789abcdef"), 6)(input)
-}
-
-pub fn eye_colour(input: &str) -> IResult<&str, &str> {
-    alt((
-        tag("amb"),
-        tag("blu"),
-        tag("brn"),
-        tag("gry"),
-        tag("grn"),
-        tag("hzl"),
-        tag("oth"),
-    ))(input)
-}
-#[derive(Debug, Copy, Clone)]
-pub enum Height {
-    Centimetres(usize),
-    Inches(usize),
-}
-
-pub fn number(input: &str) -> IResult<&str, usize> {
-    map_res(digit1, |s: &str| s.parse::<usize>())(input)
-}
-
-pub fn height(input: &str) -> IResult<&str, Height> {
-    let (input, number) = number(input)?;
-    alt((
-        value(Height::Centimetres(number), tag("cm")),
-        value(Height::Inches(number), tag("in")),
-    ))(input)
+pub fn passports(input: &str) -> IResult<&str, Vec<Passport<'_>>> {
+    groups(passport)(input)
 }
 
 #[test]
-fn test_parse_field() {
-    let inputs = vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
-    use Field::*;
-    let expected = vec![
-        BirthYear,
-        IssueYear,
-        ExpirationYear,
-        Height,
-        HairColor,
-        EyeColor,
-        PassportID,
-        CountryID,
-    ];
+fn test_parse_key() {
+    let inputs = vec!["byr", "cid", "issue_date", "x-2"];
 
-    for (input, expected) in inputs.into_iter().zip(expected.into_iter()) {
-        let (input, field) = field(input).unwrap();
-        assert_eq!(input.len(), 0);
-        assert_eq!(field, expected);
+    for input in inputs.into_iter() {
+        let (rest, key) = key(input).unwrap();
+        assert_eq!(rest.len(), 0);
+        assert_eq!(key, input);
     }
+    assert!(key(":1971").is_err());
 }
 
 #[test]
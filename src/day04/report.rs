@@ -0,0 +1,207 @@
+//! Reports of what is wrong with each passport, rather than only whether it
+//! is valid, and a summary of them across the batch.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::{schema::Schema, Passport};
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Report<'a> {
+    /// Where the passport is in the batch, counting from 1.
+    pub passport: usize,
+    /// Whether it has every required field and every value follows its rule.
+    /// Duplicate and unknown fields only count against it if the schema is
+    /// strict.
+    pub valid: bool,
+    /// The required fields it does not have.
+    pub missing: Vec<&'a str>,
+    /// The fields it has more than once.
+    pub duplicates: Vec<&'a str>,
+    /// The fields the schema does not know.
+    pub unknown: Vec<&'a str>,
+    pub invalid: Vec<Invalid<'a>>,
+}
+
+/// A value that breaks its field's rule.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Invalid<'a> {
+    pub field: &'a str,
+    pub value: &'a str,
+    /// The rule, as the schema would describe it.
+    pub rule: String,
+    pub reason: String,
+}
+
+impl<'a> Report<'a> {
+    /// Checks `passport`, the batch's `number`th, against `schema`.
+    pub fn new(number: usize, passport: &Passport<'a>, schema: &'a Schema) -> Self {
+        let fields = &passport.0;
+        let (mut duplicates, mut unknown, mut invalid) = (vec![], vec![], vec![]);
+        for (i, field) in fields.iter().enumerate() {
+            let seen = fields[..i].iter().any(|f| f.key == field.key);
+            if seen && !duplicates.contains(&field.key) {
+                duplicates.push(field.key);
+            }
+            match schema.rule(field.key) {
+                Some(rule) => {
+                    if let Err(reason) = rule.check(field.data) {
+                        invalid.push(Invalid {
+                            field: field.key,
+                            value: field.data,
+                            rule: rule.to_string(),
+                            reason,
+                        });
+                    }
+                }
+                None if !seen => unknown.push(field.key),
+                None => {}
+            }
+        }
+        let missing: Vec<&str> = schema.required().filter(|key| !passport.has(key)).collect();
+        let tidy = duplicates.is_empty() && unknown.is_empty();
+        Report {
+            passport: number,
+            valid: missing.is_empty() && invalid.is_empty() && (tidy || !schema.is_strict()),
+            missing,
+            duplicates,
+            unknown,
+            invalid,
+        }
+    }
+}
+
+/// How often each problem turns up across a batch of reports.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Summary<'a> {
+    pub passports: usize,
+    pub valid: usize,
+    /// How many passports do not have each required field.
+    pub missing: BTreeMap<&'a str, usize>,
+    /// How many passports have each field more than once.
+    pub duplicates: BTreeMap<&'a str, usize>,
+    /// How many passports have each field the schema does not know.
+    pub unknown: BTreeMap<&'a str, usize>,
+    /// How many passports have an invalid value for each field.
+    pub invalid: BTreeMap<&'a str, usize>,
+}
+
+impl<'a> Summary<'a> {
+    pub fn new(reports: &[Report<'a>]) -> Self {
+        let mut summary = Summary {
+            passports: reports.len(),
+            ..Summary::default()
+        };
+        for report in reports {
+            summary.valid += report.valid as usize;
+            let counts = [
+                (&mut summary.missing, &report.missing),
+                (&mut summary.duplicates, &report.duplicates),
+                (&mut summary.unknown, &report.unknown),
+            ];
+            for (counts, fields) in counts {
+                for field in fields {
+                    *counts.entry(*field).or_default() += 1;
+                }
+            }
+            let mut invalid: Vec<&str> = report.invalid.iter().map(|i| i.field).collect();
+            invalid.sort_unstable();
+            invalid.dedup();
+            for field in invalid {
+                *summary.invalid.entry(field).or_default() += 1;
+            }
+        }
+        summary
+    }
+}
+
+/// The summary of `reports` and the reports themselves.
+pub fn json(reports: &[Report]) -> Value {
+    json!({
+        "summary": Summary::new(reports),
+        "passports": reports,
+    })
+}
+
+#[cfg(test)]
+fn reports<'a>(input: &'a str, schema: &'a Schema) -> Vec<Report<'a>> {
+    let passports = crate::parsers::all(super::parse::passports, input).unwrap();
+    passports
+        .iter()
+        .enumerate()
+        .map(|(i, passport)| Report::new(i + 1, passport, schema))
+        .collect()
+}
+
+#[test]
+fn test_report() {
+    let input = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929 byr:1930 nat:NP
+
+hcl:dab227 iyr:2012
+ecl:brn hgt:182 pid:021572410 eyr:2020 byr:1992 cid:277
+
+ecl:gry pid:860033327 eyr:2020 hcl:#fffffd ecl:gry
+byr:1937 iyr:2017 cid:147 hgt:183cm nat:NP";
+    let reports = reports(input, Schema::puzzle());
+    assert!(reports[0].valid);
+    assert_eq!(
+        reports[1],
+        Report {
+            passport: 2,
+            valid: false,
+            missing: vec!["hgt"],
+            duplicates: vec!["byr"],
+            unknown: vec!["nat"],
+            invalid: vec![],
+        }
+    );
+    assert_eq!(
+        reports[2].invalid,
+        vec![
+            Invalid {
+                field: "hcl",
+                value: "dab227",
+                rule: "pattern /^#[0-9a-f]{6}$/".to_string(),
+                reason: "does not match /^#[0-9a-f]{6}$/".to_string(),
+            },
+            Invalid {
+                field: "hgt",
+                value: "182",
+                rule: "measure 150-193cm or 59-76in".to_string(),
+                reason: "is not measured in cm or in".to_string(),
+            },
+        ]
+    );
+
+    assert!(reports[3].valid);
+    assert_eq!(
+        (&reports[3].duplicates, &reports[3].unknown),
+        (&vec!["ecl"], &vec!["nat"])
+    );
+
+    let strict = Schema::builtin("strict").unwrap();
+    let valid: Vec<bool> = self::reports(input, strict)
+        .iter()
+        .map(|report| report.valid)
+        .collect();
+    assert_eq!(valid, vec![true, false, false, false]);
+
+    let summary = Summary::new(&reports);
+    assert_eq!((summary.passports, summary.valid), (4, 2));
+    assert_eq!(summary.missing.get("hgt"), Some(&1));
+    assert_eq!(summary.unknown.get("nat"), Some(&2));
+    assert_eq!(summary.invalid.len(), 2);
+
+    let json = json(&reports);
+    assert_eq!(json["summary"]["valid"], 2);
+    assert_eq!(json["summary"]["duplicates"]["ecl"], 1);
+    assert_eq!(json["passports"].as_array().unwrap().len(), 4);
+    assert_eq!(json["passports"][1]["duplicates"][0], "byr");
+    assert_eq!(json["passports"][2]["invalid"][1]["value"], "182");
+}
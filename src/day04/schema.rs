@@ -0,0 +1,383 @@
+//! Schemas declaring the fields of a passport and what their values must look
+//! like, so that other kinds of document can be checked without recompiling.
+//!
+//! A schema is TOML with a table for each field:
+//!
+//! ```toml
+//! [fields.byr]
+//! type = "year"
+//! min = 1920
+//! max = 2002
+//!
+//! [fields.hgt]
+//! type = "measure"
+//! units = { cm = [150, 193], in = [59, 76] }
+//!
+//! [fields.cid]
+//! required = false
+//! ```
+//!
+//! Fields are required unless they say otherwise. A passport with a field
+//! more than once, or with a field the schema does not list, is still valid
+//! unless the schema sets `strict = true` before its fields.
+//!
+//! A field's `type` is one of
+//!
+//! - `year`, a number from `min` to `max`;
+//! - `measure`, a number followed by one of `units`, each with its own range;
+//! - `pattern`, text matching `pattern`, a regular expression in the syntax
+//!   of the [`regex`] crate;
+//! - `one-of`, one of `values`;
+//! - `digits`, exactly `length` digits;
+//! - `any`, anything at all, which is what a field without a type takes.
+//!
+//! The puzzle's own rules are in `schema.toml` beside this file, and a
+//! stricter set for real passports in `strict.toml`. Both are built in.
+
+use std::{collections::BTreeMap, convert::TryFrom, fmt, path::Path, sync::OnceLock};
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    error::{Error, Result},
+    read_file,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    /// Whether duplicate and unknown fields make a passport invalid.
+    #[serde(default)]
+    strict: bool,
+    fields: BTreeMap<String, Field>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawField")]
+struct Field {
+    required: bool,
+    rule: Rule,
+}
+
+/// What a field's value must look like.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    Any,
+    Year {
+        min: u32,
+        max: u32,
+    },
+    /// The range of numbers allowed with each unit.
+    Measure(BTreeMap<String, (u32, u32)>),
+    Pattern(Regex),
+    OneOf(Vec<String>),
+    Digits(usize),
+}
+
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum Kind {
+    Any,
+    Year,
+    Measure,
+    Pattern,
+    OneOf,
+    Digits,
+}
+
+/// A field as written, with every setting any type might take.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawField {
+    #[serde(default = "required")]
+    required: bool,
+    #[serde(rename = "type", default = "any")]
+    kind: Kind,
+    min: Option<u32>,
+    max: Option<u32>,
+    units: Option<BTreeMap<String, (u32, u32)>>,
+    pattern: Option<String>,
+    values: Option<Vec<String>>,
+    length: Option<usize>,
+}
+
+fn required() -> bool {
+    true
+}
+
+fn any() -> Kind {
+    Kind::Any
+}
+
+/// Takes the setting `name` out of `setting`, which the field's type needs.
+fn take<T>(setting: &mut Option<T>, name: &str, kind: Kind) -> Result<T, String> {
+    setting
+        .take()
+        .ok_or_else(|| format!("type `{}` needs `{}`", kind, name))
+}
+
+impl TryFrom<RawField> for Field {
+    type Error = String;
+
+    fn try_from(mut raw: RawField) -> Result<Self, String> {
+        let kind = raw.kind;
+        let rule = match kind {
+            Kind::Any => Rule::Any,
+            Kind::Year => {
+                let min = take(&mut raw.min, "min", kind)?;
+                let max = take(&mut raw.max, "max", kind)?;
+                if min > max {
+                    return Err(format!("backwards years {} to {}", min, max));
+                }
+                Rule::Year { min, max }
+            }
+            Kind::Measure => {
+                let units = take(&mut raw.units, "units", kind)?;
+                if units.is_empty() {
+                    return Err("type `measure` needs at least one unit".to_string());
+                }
+                if let Some((unit, (min, max))) = units.iter().find(|(_, (min, max))| min > max) {
+                    return Err(format!("backwards range {} to {}{}", min, max, unit));
+                }
+                Rule::Measure(units)
+            }
+            Kind::Pattern => {
+                let pattern = take(&mut raw.pattern, "pattern", kind)?;
+                Rule::Pattern(Regex::new(&pattern).map_err(|err| err.to_string())?)
+            }
+            Kind::OneOf => Rule::OneOf(take(&mut raw.values, "values", kind)?),
+            Kind::Digits => Rule::Digits(take(&mut raw.length, "length", kind)?),
+        };
+        let unused = [
+            ("min", raw.min.is_some()),
+            ("max", raw.max.is_some()),
+            ("units", raw.units.is_some()),
+            ("pattern", raw.pattern.is_some()),
+            ("values", raw.values.is_some()),
+            ("length", raw.length.is_some()),
+        ];
+        if let Some((name, _)) = unused.iter().find(|(_, set)| *set) {
+            return Err(format!("`{}` does not apply to type `{}`", name, kind));
+        }
+        Ok(Field {
+            required: raw.required,
+            rule,
+        })
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Any => "any",
+            Kind::Year => "year",
+            Kind::Measure => "measure",
+            Kind::Pattern => "pattern",
+            Kind::OneOf => "one-of",
+            Kind::Digits => "digits",
+        })
+    }
+}
+
+/// The number at the start of `value`, if it has one, and the rest.
+fn number(value: &str) -> Option<(u32, &str)> {
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let n = value[..end].parse().ok()?;
+    Some((n, &value[end..]))
+}
+
+impl Rule {
+    /// Checks `value`, explaining what is wrong with it if it fails.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            Rule::Any => Ok(()),
+            Rule::Year { min, max } => match number(value) {
+                Some((year, "")) if (*min..=*max).contains(&year) => Ok(()),
+                _ => Err(format!("is not a year from {} to {}", min, max)),
+            },
+            Rule::Measure(units) => {
+                let (n, unit) = number(value).ok_or("does not start with a number")?;
+                let &(min, max) = units.get(unit).ok_or_else(|| {
+                    let units: Vec<&str> = units.keys().map(String::as_str).collect();
+                    format!("is not measured in {}", units.join(" or "))
+                })?;
+                if (min..=max).contains(&n) {
+                    Ok(())
+                } else {
+                    Err(format!("is not from {} to {}{}", min, max, unit))
+                }
+            }
+            Rule::Pattern(pattern) if pattern.is_match(value) => Ok(()),
+            Rule::Pattern(pattern) => Err(format!("does not match /{}/", pattern.as_str())),
+            Rule::OneOf(values) if values.iter().any(|v| v == value) => Ok(()),
+            Rule::OneOf(values) => Err(format!("is not one of {}", values.join(", "))),
+            Rule::Digits(length)
+                if value.len() == *length && value.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                Ok(())
+            }
+            Rule::Digits(length) => Err(format!("is not {} digits", length)),
+        }
+    }
+}
+
+/// The rule as a schema would describe it, such as `year 1920-2002`.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Any => f.write_str("any"),
+            Rule::Year { min, max } => write!(f, "year {}-{}", min, max),
+            Rule::Measure(units) => {
+                let units: Vec<String> = units
+                    .iter()
+                    .map(|(unit, (min, max))| format!("{}-{}{}", min, max, unit))
+                    .collect();
+                write!(f, "measure {}", units.join(" or "))
+            }
+            Rule::Pattern(pattern) => write!(f, "pattern /{}/", pattern.as_str()),
+            Rule::OneOf(values) => write!(f, "one of {}", values.join(", ")),
+            Rule::Digits(length) => write!(f, "{} digits", length),
+        }
+    }
+}
+
+impl Schema {
+    /// Reads a schema from a TOML file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = read_file(path)?;
+        toml::from_str(&contents)
+            .map_err(|err| Error::Params(format!("{}: {}", path.display(), err)))
+    }
+
+    /// The puzzle's schema.
+    pub fn puzzle() -> &'static Self {
+        static PUZZLE: OnceLock<Schema> = OnceLock::new();
+        PUZZLE.get_or_init(|| {
+            toml::from_str(include_str!("schema.toml")).expect("the puzzle's schema is valid")
+        })
+    }
+
+    /// The built-in schema called `name`, either `puzzle` or `strict`.
+    pub fn builtin(name: &str) -> Option<&'static Self> {
+        static STRICT: OnceLock<Schema> = OnceLock::new();
+        match name {
+            "puzzle" => Some(Schema::puzzle()),
+            "strict" => Some(STRICT.get_or_init(|| {
+                toml::from_str(include_str!("strict.toml")).expect("the strict schema is valid")
+            })),
+            _ => None,
+        }
+    }
+
+    /// The fields every passport must have.
+    pub fn required(&self) -> impl Iterator<Item = &str> {
+        self.fields
+            .iter()
+            .filter(|(_, field)| field.required)
+            .map(|(key, _)| key.as_str())
+    }
+
+    /// Whether a passport with duplicate or unknown fields is invalid.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// The rule for the field `key`, if the schema has such a field.
+    pub fn rule(&self, key: &str) -> Option<&Rule> {
+        self.fields.get(key).map(|field| &field.rule)
+    }
+}
+
+#[test]
+fn test_check() {
+    let schema = Schema::puzzle();
+    assert_eq!(
+        schema.required().collect::<Vec<_>>(),
+        vec!["byr", "ecl", "eyr", "hcl", "hgt", "iyr", "pid"]
+    );
+    let valid = [
+        ("byr", "2002"),
+        ("hgt", "60in"),
+        ("hgt", "190cm"),
+        ("hcl", "#123abc"),
+        ("ecl", "brn"),
+        ("pid", "000000001"),
+        ("cid", "anything"),
+    ];
+    let check = |key, value| schema.rule(key).unwrap().check(value);
+    for &(key, value) in &valid {
+        assert_eq!(check(key, value), Ok(()), "{}:{}", key, value);
+    }
+    let check = |key, value| check(key, value).unwrap_err();
+    assert_eq!(check("byr", "2003"), "is not a year from 1920 to 2002");
+    assert_eq!(check("byr", "1990s"), "is not a year from 1920 to 2002");
+    assert_eq!(check("hgt", "190in"), "is not from 59 to 76in");
+    assert_eq!(check("hgt", "190"), "is not measured in cm or in");
+    assert_eq!(check("hgt", "cm"), "does not start with a number");
+    assert_eq!(check("hcl", "#123abz"), "does not match /^#[0-9a-f]{6}$/");
+    assert_eq!(
+        check("ecl", "wat"),
+        "is not one of amb, blu, brn, gry, grn, hzl, oth"
+    );
+    assert_eq!(check("pid", "0123456789"), "is not 9 digits");
+    assert!(schema.rule("nat").is_none());
+
+    let rules: Vec<String> = ["byr", "hgt", "hcl", "ecl", "pid", "cid"]
+        .iter()
+        .map(|key| schema.rule(key).unwrap().to_string())
+        .collect();
+    assert_eq!(
+        rules,
+        vec![
+            "year 1920-2002",
+            "measure 150-193cm or 59-76in",
+            "pattern /^#[0-9a-f]{6}$/",
+            "one of amb, blu, brn, gry, grn, hzl, oth",
+            "9 digits",
+            "any",
+        ]
+    );
+}
+
+#[test]
+fn test_parse_schema() {
+    let parse = |text| toml::from_str::<Schema>(text).map_err(|err| err.to_string());
+    assert!(parse("[fields.id]\ntype = \"digits\"\nlength = 4").is_ok());
+    let invalid = [
+        ("[fields.id]\ntype = \"digits\"", "needs `length`"),
+        (
+            "[fields.id]\ntype = \"year\"\nmin = 2\nmax = 1",
+            "backwards years",
+        ),
+        (
+            "[fields.id]\ntype = \"any\"\nlength = 4",
+            "`length` does not apply to type `any`",
+        ),
+        (
+            "[fields.id]\ntype = \"pattern\"\npattern = \"[a\"",
+            "unclosed character class",
+        ),
+        (
+            "[fields.id]\ntype = \"measure\"\nunits = {}",
+            "at least one unit",
+        ),
+        ("[fields.id]\ntype = \"date\"", "unknown variant"),
+        ("[fields.id]\noptional = true", "unknown field"),
+    ];
+    for &(text, error) in &invalid {
+        let err = parse(text).unwrap_err();
+        assert!(err.contains(error), "{:?} gave {:?}", text, err);
+    }
+
+    let path = std::env::temp_dir().join(format!("aoc-schema-{}.toml", std::process::id()));
+    std::fs::write(&path, include_str!("strict.toml")).unwrap();
+    let strict = Schema::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(strict.is_strict() && strict.required().any(|key| key == "cid"));
+    assert!(Schema::builtin("strict").unwrap().is_strict());
+    assert!(Schema::builtin("strict.toml").is_none());
+}
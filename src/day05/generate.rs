@@ -0,0 +1,27 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+fn boarding_pass(id: usize) -> String {
+    let bit = |i: usize, zero: char, one: char| if id >> i & 1 == 1 { one } else { zero };
+    let row: String = (3..10).rev().map(|i| bit(i, 'F', 'B')).collect();
+    let col: String = (0..3).rev().map(|i| bit(i, 'L', 'R')).collect();
+    row + &col
+}
+
+/// The boarding passes of a full run of `size` seats, apart from one missing
+/// seat in the middle that is yours.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.clamp(3, 1000);
+    let first = rng.range(1..1024 - size);
+    let last = first + size - 1;
+    let mine = rng.range(first + 1..last);
+
+    let mut ids: Vec<usize> = (first..=last).filter(|&id| id != mine).collect();
+    rng.shuffle(&mut ids);
+    let passes: Vec<String> = ids.into_iter().map(boarding_pass).collect();
+    Generated::new(passes.join("\n"))
+        .answer(Part::One, last)
+        .answer(Part::Two, mine)
+}
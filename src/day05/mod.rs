@@ -1,33 +1,43 @@
-use nom::{
-    branch::alt,
-    character::complete::{char, line_ending},
-    combinator::value,
-    multi::{count, separated_list1},
-    IResult,
+use nom::multi::count;
+
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generate::{Generated, Rng},
+    parsers::{all, from_char, lines, IResult},
+    Challenge,
 };
 
-use crate::Challenge;
+mod generate;
+#[cfg(test)]
+mod reference;
 
 pub struct Day05 {
     seat_ids: Vec<usize>,
 }
 
-impl Challenge for Day05 {
+impl Challenge<'_> for Day05 {
     fn name() -> &'static str {
         "day05"
     }
-    fn new(input: String) -> Self {
-        let (_, seats) = parse_seats(&input).unwrap();
+    fn day() -> u8 {
+        5
+    }
+    fn new(input: &str) -> Result<Self> {
+        let seats = all(parse_seats, input)?;
         let seat_ids = seats.into_iter().map(to_seat_id).collect();
-        Day05 { seat_ids }
+        Ok(Day05 { seat_ids })
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_one(&self) -> usize {
-        self.seat_ids.iter().fold(0, |a, &id| a.max(id))
+    fn part_one(&self) -> Result<Answer> {
+        Ok(self.seat_ids.iter().fold(0, |a, &id| a.max(id)).into())
     }
-    fn part_two(&self) -> usize {
+    fn part_two(&self) -> Result<Answer> {
         let max_id = self.seat_ids.iter().fold(0, |a, &id| a.max(id));
         let min_id = self.seat_ids.iter().fold(max_id, |a, &id| a.min(id));
-        
+
         let mut seats: Vec<bool> = Vec::with_capacity(128 * 8);
         seats.resize(128 * 8, false);
 
@@ -37,18 +47,18 @@ impl Challenge for Day05 {
 
         for (id, &seat) in seats.iter().enumerate() {
             if !seat && (min_id..=max_id).contains(&id) {
-                return id;
+                return Ok(id.into());
             }
         }
-        panic!("no seat found");
+        Err(Error::no_solution("no seat found"))
     }
 }
 
 fn parse_fb(input: &str) -> IResult<&str, usize> {
-    alt((value(0, char('F')), value(1, char('B'))))(input)
+    from_char(&[('F', 0), ('B', 1)])(input)
 }
 fn parse_lr(input: &str) -> IResult<&str, usize> {
-    alt((value(0, char('L')), value(1, char('R'))))(input)
+    from_char(&[('L', 0), ('R', 1)])(input)
 }
 
 fn parse_row(input: &str) -> IResult<&str, usize> {
@@ -70,7 +80,7 @@ fn parse_seat(input: &str) -> IResult<&str, (usize, usize)> {
 }
 
 fn parse_seats(input: &str) -> IResult<&str, Vec<(usize, usize)>> {
-    separated_list1(line_ending, parse_seat)(input)
+    lines(parse_seat)(input)
 }
 
 fn to_seat_id((row, col): (usize, usize)) -> usize {
@@ -0,0 +1,88 @@
+//! Naive versions of day 5 that narrow down the seat one letter at a time, to
+//! check the bit folding against.
+
+use std::{collections::HashSet, ops::Range};
+
+use proptest::{collection::vec, prelude::*};
+
+use super::Day05;
+use crate::{
+    differential::{agree, solve},
+    Part,
+};
+
+/// Finds a seat by halving the rows and columns it could be in.
+fn seat_id(pass: &str) -> usize {
+    let (mut rows, mut cols): (Range<usize>, Range<usize>) = (0..128, 0..8);
+    for letter in pass.chars() {
+        let half = |range: &Range<usize>| (range.start + range.end) / 2;
+        match letter {
+            'F' => rows.end = half(&rows),
+            'B' => rows.start = half(&rows),
+            'L' => cols.end = half(&cols),
+            'R' => cols.start = half(&cols),
+            _ => panic!("bad letter {:?}", letter),
+        }
+    }
+    rows.start * 8 + cols.start
+}
+
+/// The empty seat whose neighbours are both taken.
+fn my_seat(passes: &[String]) -> Option<usize> {
+    let ids: HashSet<usize> = passes.iter().map(|pass| seat_id(pass)).collect();
+    (1..1023).find(|id| !ids.contains(id) && ids.contains(&(id - 1)) && ids.contains(&(id + 1)))
+}
+
+/// Writes a seat ID as a boarding pass.
+fn pass(id: usize) -> String {
+    (0..10)
+        .map(|bit| {
+            let set = id >> (9 - bit) & 1 == 1;
+            match (bit < 7, set) {
+                (true, false) => 'F',
+                (true, true) => 'B',
+                (false, false) => 'L',
+                (false, true) => 'R',
+            }
+        })
+        .collect()
+}
+
+fn passes() -> impl Strategy<Value = Vec<String>> {
+    vec(0..1024usize, 1..50).prop_map(|ids| ids.into_iter().map(pass).collect())
+}
+
+/// A full flight but for one seat, with the passes in any order.
+fn flight() -> impl Strategy<Value = Vec<String>> {
+    (0..900usize, 3..100usize)
+        .prop_flat_map(|(first, len)| (Just(first), Just(len), 1..len - 1))
+        .prop_flat_map(|(first, len, gap)| {
+            let ids = (first..first + len).filter(|&id| id != first + gap);
+            Just(ids.map(pass).collect::<Vec<_>>()).prop_shuffle()
+        })
+}
+
+#[test]
+fn test_highest_seat() {
+    agree(
+        passes(),
+        |passes| solve::<Day05>(&passes.join("\n"), Part::One),
+        |passes| {
+            Ok(passes
+                .iter()
+                .map(|pass| seat_id(pass))
+                .max()
+                .unwrap()
+                .into())
+        },
+    );
+}
+
+#[test]
+fn test_my_seat() {
+    agree(
+        flight(),
+        |passes| solve::<Day05>(&passes.join("\n"), Part::Two).ok(),
+        |passes| my_seat(passes).map(Into::into),
+    );
+}
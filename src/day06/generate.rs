@@ -0,0 +1,34 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+/// Groups of one to five people, each answering yes to a few questions.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let (mut anyone, mut everyone) = (0, 0);
+    let mut groups = vec![];
+    for _ in 0..size.max(1) {
+        // drawing from a few letters per group makes shared answers likely
+        let letters: Vec<char> = (0..rng.range(1..8))
+            .map(|_| (b'a' + rng.range(0..26) as u8) as char)
+            .collect();
+        let people: Vec<BTreeSet<char>> = (0..rng.range(1..6))
+            .map(|_| (0..rng.range(1..6)).map(|_| *rng.pick(&letters)).collect())
+            .collect();
+
+        let union: BTreeSet<char> = people.iter().flatten().copied().collect();
+        anyone += union.len();
+        everyone += union
+            .iter()
+            .filter(|c| people.iter().all(|p| p.contains(c)))
+            .count();
+
+        let lines: Vec<String> = people.iter().map(|p| p.iter().collect()).collect();
+        groups.push(lines.join("\n"));
+    }
+    Generated::new(groups.join("\n\n"))
+        .answer(Part::One, anyone)
+        .answer(Part::Two, everyone)
+}
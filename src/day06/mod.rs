@@ -1,31 +1,41 @@
-use nom::{
-    branch::alt,
-    character::complete::{char, line_ending},
-    combinator::value,
-    multi::{count, many1, separated_list1},
-    IResult,
+use nom::multi::many1;
+
+use crate::{
+    answer,
+    error::Result,
+    generate::{Generated, Rng},
+    parsers::{all, from_char, groups, lines, IResult},
+    Challenge,
 };
 
-use crate::Challenge;
+mod generate;
+#[cfg(test)]
+mod reference;
 
 pub struct Day06 {
     group_answers: Vec<Vec<Vec<Answer>>>,
 }
 
-impl Challenge for Day06 {
+impl Challenge<'_> for Day06 {
     fn name() -> &'static str {
         "day06"
     }
-    fn new(input: String) -> Self {
-        Day06 {
-            group_answers: parse_all_group_answers(&input).unwrap().1,
-        }
+    fn day() -> u8 {
+        6
     }
-    fn part_one(&self) -> usize {
-        count_all_group_answers(&self.group_answers) as usize
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day06 {
+            group_answers: all(parse_all_group_answers, input)?,
+        })
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_two(&self) -> usize {
-        count_all_group_answers2(&self.group_answers) as usize
+    fn part_one(&self) -> Result<answer::Answer> {
+        Ok(count_all_group_answers(&self.group_answers).into())
+    }
+    fn part_two(&self) -> Result<answer::Answer> {
+        Ok(count_all_group_answers2(&self.group_answers).into())
     }
 }
 
@@ -60,39 +70,37 @@ enum Answer {
     Z = 0x02000000,
 }
 
+const ANSWERS: [(char, Answer); 26] = [
+    ('a', Answer::A),
+    ('b', Answer::B),
+    ('c', Answer::C),
+    ('d', Answer::D),
+    ('e', Answer::E),
+    ('f', Answer::F),
+    ('g', Answer::G),
+    ('h', Answer::H),
+    ('i', Answer::I),
+    ('j', Answer::J),
+    ('k', Answer::K),
+    ('l', Answer::L),
+    ('m', Answer::M),
+    ('n', Answer::N),
+    ('o', Answer::O),
+    ('p', Answer::P),
+    ('q', Answer::Q),
+    ('r', Answer::R),
+    ('s', Answer::S),
+    ('t', Answer::T),
+    ('u', Answer::U),
+    ('v', Answer::V),
+    ('w', Answer::W),
+    ('x', Answer::X),
+    ('y', Answer::Y),
+    ('z', Answer::Z),
+];
+
 fn parse_answer(input: &str) -> IResult<&str, Answer> {
-    alt((
-        alt((
-            value(Answer::A, char('a')),
-            value(Answer::B, char('b')),
-            value(Answer::C, char('c')),
-            value(Answer::D, char('d')),
-            value(Answer::E, char('e')),
-            value(Answer::F, char('f')),
-            value(Answer::G, char('g')),
-            value(Answer::H, char('h')),
-            value(Answer::I, char('i')),
-            value(Answer::J, char('j')),
-            value(Answer::K, char('k')),
-            value(Answer::L, char('l')),
-            value(Answer::M, char('m')),
-        )),
-        alt((
-            value(Answer::N, char('n')),
-            value(Answer::O, char('o')),
-            value(Answer::P, char('p')),
-            value(Answer::Q, char('q')),
-            value(Answer::R, char('r')),
-            value(Answer::S, char('s')),
-            value(Answer::T, char('t')),
-            value(Answer::U, char('u')),
-            value(Answer::V, char('v')),
-            value(Answer::W, char('w')),
-            value(Answer::X, char('x')),
-            value(Answer::Y, char('y')),
-            value(Answer::Z, char('z')),
-        )),
-    ))(input)
+    from_char(&ANSWERS)(input)
 }
 
 fn parse_answers(input: &str) -> IResult<&str, Vec<Answer>> {
@@ -100,14 +108,14 @@ fn parse_answers(input: &str) -> IResult<&str, Vec<Answer>> {
 }
 
 fn parse_group_answers(input: &str) -> IResult<&str, Vec<Vec<Answer>>> {
-    separated_list1(line_ending, parse_answers)(input)
+    lines(parse_answers)(input)
 }
 
 fn parse_all_group_answers(input: &str) -> IResult<&str, Vec<Vec<Vec<Answer>>>> {
-    separated_list1(count(line_ending, 2), parse_group_answers)(input)
+    groups(parse_group_answers)(input)
 }
 
-fn count_group_answers(group_answers: &Vec<Vec<Answer>>) -> u32 {
+fn count_group_answers(group_answers: &[Vec<Answer>]) -> u32 {
     group_answers
         .iter()
         .fold(0, |a, answers| {
@@ -116,13 +124,13 @@ fn count_group_answers(group_answers: &Vec<Vec<Answer>>) -> u32 {
         .count_ones()
 }
 
-fn count_all_group_answers(all_group_answers: &Vec<Vec<Vec<Answer>>>) -> u32 {
+fn count_all_group_answers(all_group_answers: &[Vec<Vec<Answer>>]) -> u32 {
     all_group_answers
         .iter()
         .fold(0, |a, group_answers| a + count_group_answers(group_answers))
 }
 
-fn count_group_answers2(group_answers: &Vec<Vec<Answer>>) -> u32 {
+fn count_group_answers2(group_answers: &[Vec<Answer>]) -> u32 {
     group_answers
         .iter()
         .fold(0xffffffff, |a, answers| {
@@ -131,7 +139,7 @@ fn count_group_answers2(group_answers: &Vec<Vec<Answer>>) -> u32 {
         .count_ones()
 }
 
-fn count_all_group_answers2(all_group_answers: &Vec<Vec<Vec<Answer>>>) -> u32 {
+fn count_all_group_answers2(all_group_answers: &[Vec<Vec<Answer>>]) -> u32 {
     all_group_answers.iter().fold(0, |a, group_answers| {
         a + count_group_answers2(group_answers)
     })
@@ -0,0 +1,62 @@
+//! Naive versions of day 6 using sets of characters, to check the bitmasks
+//! against.
+
+use std::collections::HashSet;
+
+use proptest::{char, collection::vec, prelude::*};
+
+use super::Day06;
+use crate::{
+    differential::{agree, solve},
+    Part,
+};
+
+type Group = Vec<Vec<char>>;
+
+fn anyone(group: &Group) -> usize {
+    group.iter().flatten().collect::<HashSet<_>>().len()
+}
+
+fn everyone(group: &Group) -> usize {
+    let mut answers = group
+        .iter()
+        .map(|person| person.iter().collect::<HashSet<_>>());
+    let first = answers.next().unwrap_or_default();
+    answers.fold(first, |all, person| &all & &person).len()
+}
+
+/// Groups of people who each answered yes to a few questions, possibly more
+/// than once.
+fn groups() -> impl Strategy<Value = Vec<Group>> {
+    let person = vec(char::range('a', 'z'), 1..8);
+    vec(vec(person, 1..6), 1..20)
+}
+
+fn input(groups: &[Group]) -> String {
+    let groups: Vec<String> = groups
+        .iter()
+        .map(|group| {
+            let people: Vec<String> = group.iter().map(|person| person.iter().collect()).collect();
+            people.join("\n")
+        })
+        .collect();
+    groups.join("\n\n")
+}
+
+#[test]
+fn test_anyone() {
+    agree(
+        groups(),
+        |groups| solve::<Day06>(&input(groups), Part::One),
+        |groups| Ok(groups.iter().map(anyone).sum::<usize>().into()),
+    );
+}
+
+#[test]
+fn test_everyone() {
+    agree(
+        groups(),
+        |groups| solve::<Day06>(&input(groups), Part::Two),
+        |groups| Ok(groups.iter().map(everyone).sum::<usize>().into()),
+    );
+}
@@ -0,0 +1,80 @@
+use std::collections::HashSet;
+
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+const ADJECTIVES: [&str; 20] = [
+    "light", "dark", "bright", "muted", "shiny", "faded", "dotted", "vibrant", "pale", "clear",
+    "dull", "posh", "wavy", "striped", "drab", "dim", "plaid", "mirrored", "dusty", "shabby",
+];
+const COLOURS: [&str; 20] = [
+    "red", "orange", "white", "yellow", "gold", "olive", "plum", "blue", "black", "green",
+    "violet", "teal", "tan", "cyan", "lime", "maroon", "silver", "bronze", "beige", "coral",
+];
+
+/// Rules for `size` bags, where a bag only ever contains bags that come after
+/// it, so the rules never loop. Shiny gold is placed near the end so the number
+/// of bags inside it stays small.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut names: Vec<String> = ADJECTIVES
+        .iter()
+        .flat_map(|a| COLOURS.iter().map(move |c| format!("{} {}", a, c)))
+        .filter(|name| name != "shiny gold")
+        .collect();
+    rng.shuffle(&mut names);
+    names.truncate(size.clamp(2, 300) - 1);
+    let gold = names.len() - rng.range(0..names.len().min(10));
+    names.insert(gold, "shiny gold".to_string());
+
+    let contains: Vec<Vec<(usize, usize)>> = (0..names.len())
+        .map(|i| {
+            let mut inner: Vec<usize> = (0..rng.range(0..5))
+                .filter(|_| i + 1 < names.len())
+                .map(|_| rng.range(i + 1..names.len()))
+                .collect();
+            inner.sort_unstable();
+            inner.dedup();
+            inner.into_iter().map(|j| (rng.range(1..6), j)).collect()
+        })
+        .collect();
+
+    // bags that can eventually hold shiny gold, found by walking back from it
+    let mut holders = HashSet::new();
+    let mut stack = vec![gold];
+    while let Some(bag) = stack.pop() {
+        for (i, inner) in contains.iter().enumerate() {
+            if inner.iter().any(|&(_, j)| j == bag) && holders.insert(i) {
+                stack.push(i);
+            }
+        }
+    }
+
+    // bags inside each bag from shiny gold on, counted from the last bag backwards
+    let mut inside = vec![0; names.len()];
+    for i in (gold..names.len()).rev() {
+        inside[i] = contains[i].iter().map(|&(n, j)| n * (1 + inside[j])).sum();
+    }
+
+    let mut rules: Vec<String> = contains
+        .iter()
+        .enumerate()
+        .map(|(i, inner)| {
+            let inner: Vec<String> = inner
+                .iter()
+                .map(|&(n, j)| format!("{} {} bag{}", n, names[j], if n == 1 { "" } else { "s" }))
+                .collect();
+            let inner = if inner.is_empty() {
+                "no other bags".to_string()
+            } else {
+                inner.join(", ")
+            };
+            format!("{} bags contain {}.", names[i], inner)
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+    Generated::new(rules.join("\n"))
+        .answer(Part::One, holders.len())
+        .answer(Part::Two, inside[gold])
+}
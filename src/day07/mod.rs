@@ -1,55 +1,98 @@
+mod generate;
 mod parse;
 
-use crate::Challenge;
+use serde::Deserialize;
 
-pub struct Day07 {
-    rules: Vec<Rule>,
+use crate::{
+    answer::Answer,
+    config::Overrides,
+    error::{Error, Result},
+    generate::{Generated, Rng},
+    parsers::all,
+    Challenge, Solver,
+};
+
+pub struct Day07<'a> {
+    rules: Vec<Rule<'a>>,
+    params: Params,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// The bag both parts ask about.
+    pub bag: String,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            bag: "shiny gold".to_string(),
+        }
+    }
+}
+
+impl Solver for Day07<'static> {
+    type For<'a> = Day07<'a>;
 }
 
-impl Challenge for Day07 {
+impl<'a> Challenge<'a> for Day07<'a> {
     fn name() -> &'static str {
         "day07"
     }
-    fn new(input: String) -> Self {
-        Day07 {
-            rules: parse::rules(&input).unwrap().1,
-        }
+    fn day() -> u8 {
+        7
+    }
+    fn new(input: &'a str) -> Result<Self> {
+        Ok(Day07 {
+            rules: all(parse::rules, input)?,
+            params: Params::default(),
+        })
+    }
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        self.params = overrides.apply()?;
+        Ok(())
     }
-    fn part_one(&self) -> usize {
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+    fn part_one(&self) -> Result<Answer> {
         let contained_map = rules_into_contained_map(&self.rules);
-        can_hold(&contained_map, "shiny gold".to_string()).len()
+        Ok(can_hold(&contained_map, &self.params.bag)?.len().into())
     }
-    fn part_two(&self) -> usize {
-        MustContain::new(&self.rules).must_contain("shiny gold".to_string()) - 1
+    fn part_two(&self) -> Result<Answer> {
+        MustContain::new(&self.rules)
+            .must_contain(&self.params.bag)
+            .map(|count| (count - 1).into())
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Rule {
-    bag_name: String,
-    contains: Vec<(usize, String)>,
+pub struct Rule<'a> {
+    bag_name: &'a str,
+    contains: Vec<(usize, &'a str)>,
 }
 
 use std::collections::HashMap;
-fn rules_into_contain_map(rules: &Vec<Rule>) -> HashMap<String, Vec<(usize, String)>> {
+fn rules_into_contain_map<'a>(rules: &[Rule<'a>]) -> HashMap<&'a str, Vec<(usize, &'a str)>> {
     let mut map = HashMap::new();
 
     for rule in rules {
-        map.insert(rule.bag_name.clone(), rule.contains.clone());
+        map.insert(rule.bag_name, rule.contains.clone());
     }
 
     map
 }
 
-fn rules_into_contained_map(rules: &Vec<Rule>) -> HashMap<String, Vec<String>> {
-    let mut map: HashMap<String, Vec<String>> = HashMap::new();
+fn rules_into_contained_map<'a>(rules: &[Rule<'a>]) -> HashMap<&'a str, Vec<&'a str>> {
+    let mut map: HashMap<&str, Vec<&str>> = HashMap::new();
 
     for rule in rules {
-        for (_, contain) in &rule.contains {
+        for &(_, contain) in &rule.contains {
             if let Some(v) = map.get_mut(contain) {
-                v.push(rule.bag_name.clone());
+                v.push(rule.bag_name);
             } else {
-                map.insert(contain.clone(), vec![rule.bag_name.clone()]);
+                map.insert(contain, vec![rule.bag_name]);
             }
         }
     }
@@ -57,58 +100,106 @@ fn rules_into_contained_map(rules: &Vec<Rule>) -> HashMap<String, Vec<String>> {
     map
 }
 
+/// The error for rules that put `bag` inside itself, by way of the bags after
+/// it on `path`.
+fn cycle(path: &[&str], bag: &str) -> Error {
+    let start = path.iter().position(|&b| b == bag).unwrap_or(0);
+    let mut bags = path[start..].to_vec();
+    bags.push(bag);
+    Error::no_solution(format!(
+        "{} bags end up inside themselves: {}",
+        bag,
+        bags.join(", ")
+    ))
+}
+
 use std::collections::HashSet;
-fn can_hold(
-    contained_map: &HashMap<String, Vec<String>>,
-    bag_name: String,
-) -> HashSet<String> {
+fn can_hold<'a>(
+    contained_map: &HashMap<&'a str, Vec<&'a str>>,
+    bag_name: &str,
+) -> Result<HashSet<&'a str>> {
     let mut can_hold_set = HashSet::new();
+    find_holders(contained_map, bag_name, &mut vec![], &mut can_hold_set)?;
+    Ok(can_hold_set)
+}
 
-    if !contained_map.contains_key(&bag_name) {
-        return can_hold_set;
-    }
-
-    for contained in contained_map[&bag_name].clone() {
-        can_hold_set.insert(contained.clone());
+/// Adds every bag that can hold `bag_name` to `found`, where `path` is the
+/// chain of bags that led to `bag_name`.
+fn find_holders<'a>(
+    contained_map: &HashMap<&'a str, Vec<&'a str>>,
+    bag_name: &str,
+    path: &mut Vec<&'a str>,
+    found: &mut HashSet<&'a str>,
+) -> Result<()> {
+    let holders = match contained_map.get(bag_name) {
+        Some(holders) => holders,
+        None => return Ok(()),
+    };
 
-        let c = can_hold(contained_map, contained);
-        for s in c {
-            can_hold_set.insert(s);
+    for &holder in holders {
+        if path.contains(&holder) {
+            return Err(cycle(path, holder));
         }
+        found.insert(holder);
+
+        path.push(holder);
+        find_holders(contained_map, holder, path, found)?;
+        path.pop();
     }
 
-    can_hold_set
+    Ok(())
 }
 
 use std::cell::RefCell;
-struct MustContain {
-    cache: RefCell<HashMap<String, usize>>,
-    contain_map: HashMap<String, Vec<(usize, String)>>,
+struct MustContain<'a> {
+    cache: RefCell<HashMap<&'a str, usize>>,
+    contain_map: HashMap<&'a str, Vec<(usize, &'a str)>>,
 }
 
-impl MustContain {
-    pub fn new(rules: &Vec<Rule>) -> Self {
+impl<'a> MustContain<'a> {
+    pub fn new(rules: &[Rule<'a>]) -> Self {
         MustContain {
             cache: RefCell::new(HashMap::new()),
             contain_map: rules_into_contain_map(rules),
         }
     }
 
-    pub fn must_contain(&self, bag_name: String) -> usize {
+    /// Counts the bags inside `bag_name`, including itself.
+    /// Fails if any bag along the way has no rule or ends up inside itself.
+    pub fn must_contain(&self, bag_name: &str) -> Result<usize> {
+        self.count(bag_name, &mut vec![])
+    }
+
+    /// Counts the bags inside `bag_name`, where `path` is the chain of bags
+    /// that hold it.
+    fn count(&self, bag_name: &str, path: &mut Vec<&'a str>) -> Result<usize> {
         {
-            if let Some(&count) = self.cache.borrow().get(&bag_name) {
-                return count;
+            if let Some(&count) = self.cache.borrow().get(bag_name) {
+                return Ok(count);
             }
         }
-        let result = self.contain_map[&bag_name]
-            .iter()
-            .fold(1, |acc, (amount, name)| {
-                acc + amount * self.must_contain(name.clone())
-            });
+        let (&bag_name, contains) = self
+            .contain_map
+            .get_key_value(bag_name)
+            .ok_or_else(|| Error::no_solution(format!("missing a rule for {} bags", bag_name)))?;
+        if path.contains(&bag_name) {
+            return Err(cycle(path, bag_name));
+        }
+
+        path.push(bag_name);
+        let result = contains.iter().try_fold(1usize, |acc, &(amount, name)| {
+            let inside = self.count(name, path)?;
+            amount
+                .checked_mul(inside)
+                .and_then(|inside| acc.checked_add(inside))
+                .ok_or_else(|| Error::no_solution(format!("{} bags hold too many bags", bag_name)))
+        });
+        path.pop();
+        let result = result?;
 
         self.cache.borrow_mut().insert(bag_name, result);
 
-        result
+        Ok(result)
     }
 }
 
@@ -128,28 +219,28 @@ dotted black bags contain no other bags.";
     let map = rules_into_contain_map(&rules);
     assert_eq!(
         map["light red"],
-        vec![(1, "bright white".to_string()), (2, "muted yellow".to_string())]
+        vec![(1, "bright white"), (2, "muted yellow")]
     );
     assert_eq!(
         map["dark orange"],
-        vec![(3, "bright white".to_string()), (4, "muted yellow".to_string())]
+        vec![(3, "bright white"), (4, "muted yellow")]
     );
-    assert_eq!(map["bright white"], vec![(1, "shiny gold".to_string())]);
+    assert_eq!(map["bright white"], vec![(1, "shiny gold")]);
     assert_eq!(
         map["muted yellow"],
-        vec![(2, "shiny gold".to_string()), (9, "faded blue".to_string())]
+        vec![(2, "shiny gold"), (9, "faded blue")]
     );
     assert_eq!(
         map["shiny gold"],
-        vec![(1, "dark olive".to_string()), (2, "vibrant plum".to_string())]
+        vec![(1, "dark olive"), (2, "vibrant plum")]
     );
     assert_eq!(
         map["dark olive"],
-        vec![(3, "faded blue".to_string()), (4, "dotted black".to_string())]
+        vec![(3, "faded blue"), (4, "dotted black")]
     );
     assert_eq!(
         map["vibrant plum"],
-        vec![(5, "faded blue".to_string()), (6, "dotted black".to_string())]
+        vec![(5, "faded blue"), (6, "dotted black")]
     );
     assert_eq!(map["faded blue"], vec![]);
     assert_eq!(map["dotted black"], vec![]);
@@ -195,7 +286,7 @@ dotted black bags contain no other bags.";
 
     let (_, rules) = parse::rules(input).unwrap();
     let contained_map = rules_into_contained_map(&rules);
-    let can_hold_set = can_hold(&contained_map, "shiny gold".to_string());
+    let can_hold_set = can_hold(&contained_map, "shiny gold").unwrap();
     assert_eq!(can_hold_set.len(), 4);
     assert!(can_hold_set.contains("light red"));
     assert!(can_hold_set.contains("dark orange"));
@@ -216,7 +307,7 @@ faded blue bags contain no other bags.
 dotted black bags contain no other bags.";
 
     let (_, rules) = parse::rules(input).unwrap();
-    let count = MustContain::new(&rules).must_contain("shiny gold".to_string()) - 1;
+    let count = MustContain::new(&rules).must_contain("shiny gold").unwrap() - 1;
     assert_eq!(count, 32);
 }
 
@@ -231,6 +322,28 @@ dark blue bags contain 2 dark violet bags.
 dark violet bags contain no other bags.";
 
     let (_, rules) = parse::rules(input).unwrap();
-    let count = MustContain::new(&rules).must_contain("shiny gold".to_string()) - 1;
+    let count = MustContain::new(&rules).must_contain("shiny gold").unwrap() - 1;
     assert_eq!(count, 126);
 }
+
+#[test]
+fn test_cycle() {
+    let input = "shiny gold bags contain 1 dark red bag.
+dark red bags contain 1 shiny gold bag.";
+
+    let (_, rules) = parse::rules(input).unwrap();
+    let contained_map = rules_into_contained_map(&rules);
+    assert!(matches!(
+        can_hold(&contained_map, "shiny gold"),
+        Err(Error::NoSolution(_))
+    ));
+    assert_eq!(
+        MustContain::new(&rules)
+            .must_contain("shiny gold")
+            .unwrap_err()
+            .to_string(),
+        "no solution: shiny gold bags end up inside themselves: shiny gold, dark red, shiny gold"
+    );
+    let missing = MustContain::new(&rules[..1]).must_contain("shiny gold");
+    assert!(matches!(missing, Err(Error::NoSolution(_))));
+}
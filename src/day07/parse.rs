@@ -1,48 +1,39 @@
 use super::Rule;
+use crate::parsers::{comma_list, lines, number, IResult};
 
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_until},
-    character::complete::{digit1, line_ending, space1},
-    combinator::map_res,
+    character::complete::space1,
     combinator::value,
-    multi::separated_list1,
+    error::context,
     sequence::{separated_pair, terminated},
-    IResult,
 };
 
-pub fn number(input: &str) -> IResult<&str, usize> {
-    map_res(digit1, |s: &str| s.parse::<usize>())(input)
-}
-
-pub fn contain(input: &str) -> IResult<&str, (usize, String)> {
-    let (input, (n, s)) = separated_pair(
+pub fn contain(input: &str) -> IResult<&str, (usize, &str)> {
+    separated_pair(
         number,
         space1,
         terminated(take_until(" bag"), alt((tag(" bags"), tag(" bag")))),
-    )(input)?;
-    Ok((input, (n, s.to_string())))
+    )(input)
 }
 
-pub fn contains(input: &str) -> IResult<&str, Vec<(usize, String)>> {
+pub fn contains(input: &str) -> IResult<&str, Vec<(usize, &str)>> {
     terminated(
-        alt((
-            value(vec![], tag("no other bags")),
-            separated_list1(tag(", "), contain),
-        )),
+        alt((value(vec![], tag("no other bags")), comma_list(contain))),
         tag("."),
     )(input)
 }
 
-pub fn rule(input: &str) -> IResult<&str, Rule> {
+pub fn rule(input: &str) -> IResult<&str, Rule<'_>> {
     let (input, bag_name) = terminated(take_until(" bags contain "), tag(" bags contain "))(input)?;
-    let (input, contains) = contains(input)?;
+    let (input, contains) = context("contained bags", contains)(input)?;
 
-    Ok((input, Rule { bag_name: bag_name.to_string(), contains }))
+    Ok((input, Rule { bag_name, contains }))
 }
 
-pub fn rules(input: &str) -> IResult<&str, Vec<Rule>> {
-    separated_list1(line_ending, rule)(input)
+pub fn rules(input: &str) -> IResult<&str, Vec<Rule<'_>>> {
+    lines(rule)(input)
 }
 
 #[test]
@@ -63,39 +54,39 @@ dotted black bags contain no other bags.";
         rules,
         vec![
             Rule {
-                bag_name: "light red".to_string(),
-                contains: vec![(1, "bright white".to_string()), (2, "muted yellow".to_string())]
+                bag_name: "light red",
+                contains: vec![(1, "bright white"), (2, "muted yellow")]
             },
             Rule {
-                bag_name: "dark orange".to_string(),
-                contains: vec![(3, "bright white".to_string()), (4, "muted yellow".to_string())]
+                bag_name: "dark orange",
+                contains: vec![(3, "bright white"), (4, "muted yellow")]
             },
             Rule {
-                bag_name: "bright white".to_string(),
-                contains: vec![(1, "shiny gold".to_string())]
+                bag_name: "bright white",
+                contains: vec![(1, "shiny gold")]
             },
             Rule {
-                bag_name: "muted yellow".to_string(),
-                contains: vec![(2, "shiny gold".to_string()), (9, "faded blue".to_string())]
+                bag_name: "muted yellow",
+                contains: vec![(2, "shiny gold"), (9, "faded blue")]
             },
             Rule {
-                bag_name: "shiny gold".to_string(),
-                contains: vec![(1, "dark olive".to_string()), (2, "vibrant plum".to_string())]
+                bag_name: "shiny gold",
+                contains: vec![(1, "dark olive"), (2, "vibrant plum")]
             },
             Rule {
-                bag_name: "dark olive".to_string(),
-                contains: vec![(3, "faded blue".to_string()), (4, "dotted black".to_string())]
+                bag_name: "dark olive",
+                contains: vec![(3, "faded blue"), (4, "dotted black")]
             },
             Rule {
-                bag_name: "vibrant plum".to_string(),
-                contains: vec![(5, "faded blue".to_string()), (6, "dotted black".to_string())]
+                bag_name: "vibrant plum",
+                contains: vec![(5, "faded blue"), (6, "dotted black")]
             },
             Rule {
-                bag_name: "faded blue".to_string(),
+                bag_name: "faded blue",
                 contains: vec![]
             },
             Rule {
-                bag_name: "dotted black".to_string(),
+                bag_name: "dotted black",
                 contains: vec![]
             },
         ]
@@ -0,0 +1,54 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+/// A boot code of about `size` instructions with one corrupted `jmp`.
+///
+/// Until the corrupted instruction there are only `acc`s and `nop`s, which
+/// run in order. The corruption jumps back to one of them, so the code loops,
+/// and it is the only instruction whose repair gets past it: any other `nop`
+/// turned into a `jmp` points backwards too. After it, `jmp`s only go forwards,
+/// so the repaired code always terminates.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.max(2);
+    let corrupted = rng.range(1..size / 2 + 1);
+
+    let mut program = vec![];
+    let mut before = 0;
+    for ip in 0..size {
+        let back = -rng.signed(0..ip as isize + 1);
+        let instruction = match rng.range(0..3) {
+            _ if ip == corrupted => format!("jmp {:+}", back),
+            0 if ip > corrupted => format!("jmp {:+}", rng.range(1..size - ip + 1)),
+            1 => format!("nop {:+}", back),
+            _ => {
+                let n = rng.signed(-50..51);
+                if ip < corrupted {
+                    before += n;
+                }
+                format!("acc {:+}", n)
+            }
+        };
+        program.push(instruction);
+    }
+
+    // after the repair every instruction is run, apart from those jumped over
+    let mut ip = corrupted + 1;
+    let mut repaired = before;
+    while let Some(instruction) = program.get(ip) {
+        let n: isize = instruction[4..].parse().unwrap();
+        match &instruction[..3] {
+            "jmp" => ip += n as usize,
+            "acc" => {
+                repaired += n;
+                ip += 1
+            }
+            _ => ip += 1,
+        }
+    }
+
+    Generated::new(program.join("\n"))
+        .answer(Part::One, before)
+        .answer(Part::Two, repaired)
+}
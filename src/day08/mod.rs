@@ -1,29 +1,44 @@
+mod generate;
 mod parse;
 
-use crate::Challenge;
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generate::{Generated, Rng},
+    parsers::all,
+    Challenge,
+};
 
 pub struct Day08 {
     instructions: Vec<Instruction>,
 }
 
-impl Challenge for Day08 {
+impl Challenge<'_> for Day08 {
     fn name() -> &'static str {
         "day08"
     }
-    fn new(input: String) -> Self {
-        Day08 {
-            instructions: parse::program(&input).unwrap().1,
-        }
+    fn day() -> u8 {
+        8
+    }
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day08 {
+            instructions: all(parse::program, input)?,
+        })
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_one(&self) -> usize {
+    fn part_one(&self) -> Result<Answer> {
         let mut vm = VM::new(self.instructions.clone());
         match vm.run() {
-            Ok(acc) => panic!(format!("program terminated! acc: {}", acc)),
-            Err(acc) => acc as usize,
+            Ok(acc) => Err(Error::no_solution(format!("program terminated! acc: {}", acc))),
+            Err(acc) => Ok(acc.into()),
         }
     }
-    fn part_two(&self) -> usize {
-        fix_program(self.instructions.clone()) as usize
+    fn part_two(&self) -> Result<Answer> {
+        fix_program(self.instructions.clone())
+            .map(Answer::from)
+            .ok_or_else(|| Error::no_solution("could not fix program"))
     }
 }
 
@@ -125,7 +140,7 @@ impl Iterator for InstructionIter {
     }
 }
 
-fn fix_program(instructions: Vec<Instruction>) -> isize {
+fn fix_program(instructions: Vec<Instruction>) -> Option<isize> {
     let iter = InstructionIter {
         instructions,
         ip: 0,
@@ -135,10 +150,10 @@ fn fix_program(instructions: Vec<Instruction>) -> isize {
         let mut vm = VM::new(instructions);
 
         if let Ok(acc) = vm.run() {
-            return acc;
+            return Some(acc);
         }
     }
-    panic!("could not fix program")
+    None
 }
 
 #[test]
@@ -196,5 +211,5 @@ fn test_program_step_fix() {
         Acc(6),
     ];
     let acc = fix_program(instructions);
-    assert_eq!(acc, 8)
+    assert_eq!(acc, Some(8))
 }
@@ -1,33 +1,21 @@
-use crate::parsers::number;
+use crate::parsers::{lines, signed, IResult};
 
 use super::Instruction;
 
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{char, line_ending, space1},
-    combinator::value,
-    multi::separated_list1,
-    sequence::separated_pair,
-    IResult,
+    branch::alt, bytes::complete::tag, character::complete::space1, sequence::separated_pair,
 };
 
-pub fn signed_number(input: &str) -> IResult<&str, isize> {
-    let (input, sign) = alt((value(-1, char('-')), value(1, char('+'))))(input)?;
-    let (input, abs) = number::<isize>(input)?;
-    Ok((input, sign * abs))
-}
-
 pub fn nop(input: &str) -> IResult<&str, Instruction> {
-    let (input, (_, value)) = separated_pair(tag("nop"), space1, signed_number)(input)?;
+    let (input, (_, value)) = separated_pair(tag("nop"), space1, signed)(input)?;
     Ok((input, Instruction::Nop(value)))
 }
 pub fn jmp(input: &str) -> IResult<&str, Instruction> {
-    let (input, (_, value)) = separated_pair(tag("jmp"), space1, signed_number)(input)?;
+    let (input, (_, value)) = separated_pair(tag("jmp"), space1, signed)(input)?;
     Ok((input, Instruction::Jmp(value)))
 }
 pub fn acc(input: &str) -> IResult<&str, Instruction> {
-    let (input, (_, value)) = separated_pair(tag("acc"), space1, signed_number)(input)?;
+    let (input, (_, value)) = separated_pair(tag("acc"), space1, signed)(input)?;
     Ok((input, Instruction::Acc(value)))
 }
 pub fn instruction(input: &str) -> IResult<&str, Instruction> {
@@ -35,7 +23,7 @@ pub fn instruction(input: &str) -> IResult<&str, Instruction> {
 }
 
 pub fn program(input: &str) -> IResult<&str, Vec<Instruction>> {
-    separated_list1(line_ending, instruction)(input)
+    lines(instruction)(input)
 }
 
 #[cfg(test)]
@@ -0,0 +1,65 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+const PREAMBLE: usize = 25;
+
+fn is_sum(window: &[usize], n: usize) -> bool {
+    window.iter().any(|&a| a <= n && window.contains(&(n - a)))
+}
+
+/// About `size` numbers (at most 1000, as they roughly double every 25) in
+/// which one number is not the sum of two of the 25 before it, but is the sum
+/// of a contiguous run of earlier numbers.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.clamp(PREAMBLE + 10, 1000);
+    let invalid = rng.range(PREAMBLE + 5..size);
+
+    let mut numbers: Vec<usize> = (1..PREAMBLE * 3).collect();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(PREAMBLE);
+    while numbers.len() < size {
+        let i = numbers.len();
+        if i == invalid {
+            // the sum of a short run that is not the sum of a pair
+            let start = rng.range(0..i - 3);
+            let end = rng.range(start + 2..(start + 10).min(i));
+            let sum = numbers[start..end].iter().sum();
+            if is_sum(&numbers[i - PREAMBLE..], sum) || numbers.contains(&sum) {
+                continue;
+            }
+            numbers.push(sum);
+            continue;
+        }
+        // summing small numbers keeps the growth slow
+        let mut window = numbers[i - PREAMBLE..].to_vec();
+        window.sort_unstable();
+        window.dedup();
+        let a = rng.range(0..6);
+        let b = (a + rng.range(1..6)) % 6;
+        numbers.push(window[a] + window[b]);
+    }
+
+    // the run that ends first, as that is the one found by scanning
+    let target = numbers[invalid];
+    let prefix: Vec<usize> = std::iter::once(0)
+        .chain(numbers.iter().scan(0, |sum, n| {
+            *sum += n;
+            Some(*sum)
+        }))
+        .collect();
+    let run = (1..=numbers.len())
+        .find_map(|end| {
+            (0..end)
+                .find(|&start| prefix[end] - prefix[start] == target)
+                .map(|start| &numbers[start..end])
+        })
+        .unwrap();
+    let weakness = run.iter().min().unwrap() + run.iter().max().unwrap();
+
+    let input: Vec<String> = numbers.iter().map(usize::to_string).collect();
+    Generated::new(input.join("\n"))
+        .answer(Part::One, target)
+        .answer(Part::Two, weakness)
+}
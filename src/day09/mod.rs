@@ -1,29 +1,66 @@
+use serde::Deserialize;
+
+use crate::answer::Answer;
+use crate::config::Overrides;
+use crate::error::{Error, Result};
+use crate::generate::{Generated, Rng};
 use crate::parsers::{all, lines, number};
 use crate::Challenge;
 
+mod generate;
+
 pub struct Day09 {
     numbers: Vec<usize>,
+    params: Params,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// How many numbers before each one it may be the sum of.
+    pub preamble: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { preamble: 25 }
+    }
 }
 
-impl Challenge for Day09 {
+impl Challenge<'_> for Day09 {
     fn name() -> &'static str {
         "day09"
     }
-    fn new(input: String) -> Self {
-        Day09 {
-            numbers: all(lines(number)(&input)),
-        }
+    fn day() -> u8 {
+        9
+    }
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day09 {
+            numbers: all(lines(number), input)?,
+            params: Params::default(),
+        })
     }
-    fn part_one(&self) -> usize {
-        find_invalid(&self.numbers, 25).unwrap()
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        self.params = overrides.apply()?;
+        Ok(())
     }
-    fn part_two(&self) -> usize {
-        let invalid = find_invalid(&self.numbers, 25).unwrap();
-        let contiguous_sum = find_sum_contiguous(&self.numbers, invalid);
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
+    }
+    fn part_one(&self) -> Result<Answer> {
+        find_invalid(&self.numbers, self.params.preamble)
+            .map(Answer::from)
+            .ok_or_else(|| Error::no_solution("every number is valid"))
+    }
+    fn part_two(&self) -> Result<Answer> {
+        let invalid = find_invalid(&self.numbers, self.params.preamble)
+            .ok_or_else(|| Error::no_solution("every number is valid"))?;
+        let contiguous_sum = find_sum_contiguous(&self.numbers, invalid)
+            .ok_or_else(|| Error::no_solution(format!("no contiguous range sums to {}", invalid)))?;
         let max = contiguous_sum.iter().max().unwrap();
         let min = contiguous_sum.iter().min().unwrap();
 
-        min + max
+        Ok((min + max).into())
     }
 }
 
@@ -50,24 +87,25 @@ fn find_invalid(numbers: &[usize], length: usize) -> Option<usize> {
     None
 }
 
-fn find_sum_contiguous(numbers: &[usize], sum: usize) -> &[usize] {
+fn find_sum_contiguous(numbers: &[usize], sum: usize) -> Option<&[usize]> {
     let mut i = 0;
     let mut j = 1;
-    loop {
+    while j <= numbers.len() {
         let s: usize = numbers[i..j].iter().sum();
-        if s == sum {
-            return &numbers[i..j];
+        if s == sum && i < j {
+            return Some(&numbers[i..j]);
         } else if s > sum {
             i += 1;
         } else {
             j += 1;
         }
     }
+    None
 }
 
 #[test]
 fn find_sum_pair_test() {
-    let output = find_sum_pair(&vec![1721, 979, 366, 299, 675, 1456], 2020);
+    let output = find_sum_pair(&[1721, 979, 366, 299, 675, 1456], 2020);
     assert_eq!(output, Some((1721, 299)));
 }
 
@@ -86,5 +124,5 @@ fn test_sum_contiguous() {
         35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
     ];
     let sum = find_sum_contiguous(&numbers, 127);
-    assert_eq!(sum, vec![15, 25, 47, 40])
+    assert_eq!(sum, Some(&[15, 25, 47, 40][..]))
 }
@@ -0,0 +1,41 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+/// About `size` adapters (at most 100, so the number of arrangements fits in a
+/// `u64`) that differ by one or three jolts, with no more than four differences
+/// of one in a row. As in real inputs, the first adapter is rated 1 jolt.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let size = size.clamp(1, 100);
+    let mut adapters = vec![1];
+    let mut run = 1;
+    while adapters.len() < size {
+        let step = if run < 4 && rng.chance(2, 3) { 1 } else { 3 };
+        run = if step == 1 { run + 1 } else { 0 };
+        adapters.push(adapters.last().unwrap() + step);
+    }
+
+    let mut joltages = vec![0];
+    joltages.extend(&adapters);
+    joltages.push(adapters.last().unwrap() + 3);
+    let diffs: Vec<usize> = joltages.windows(2).map(|w| w[1] - w[0]).collect();
+    let ones = diffs.iter().filter(|&&d| d == 1).count();
+    let threes = diffs.iter().filter(|&&d| d == 3).count();
+
+    // ways[i] counts the ways to reach joltages[i] from the outlet
+    let mut ways = vec![0u64; joltages.len()];
+    ways[0] = 1;
+    for i in 1..joltages.len() {
+        ways[i] = (0..i)
+            .filter(|&j| joltages[i] - joltages[j] <= 3)
+            .map(|j| ways[j])
+            .sum();
+    }
+
+    rng.shuffle(&mut adapters);
+    let input: Vec<String> = adapters.iter().map(usize::to_string).collect();
+    Generated::new(input.join("\n"))
+        .answer(Part::One, ones * threes)
+        .answer(Part::Two, *ways.last().unwrap())
+}
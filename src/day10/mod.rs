@@ -1,34 +1,50 @@
+use crate::answer::Answer;
+use crate::error::{Error, Result};
+use crate::generate::{Generated, Rng};
 use crate::parsers::{lines, number, all};
 use crate::Challenge;
 
+mod generate;
+#[cfg(test)]
+mod reference;
+
 pub struct Day10 {
     runs: Vec<usize>,
 }
 
-impl Challenge for Day10 {
+impl Challenge<'_> for Day10 {
     fn name() -> &'static str {
         "day10"
     }
-    fn new(input: String) -> Self {
-        Day10 {
-            runs: runs(all(lines(number)(&input))),
-        }
+    fn day() -> u8 {
+        10
+    }
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day10 {
+            runs: runs(all(lines(number), input)?)?,
+        })
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_one(&self) -> usize {
+    fn part_one(&self) -> Result<Answer> {
         let (ones, threes) = gaps(&self.runs);
-        ones * threes
+        Ok((ones * threes).into())
     }
-    fn part_two(&self) -> usize {
-        arrangements(&self.runs)
+    fn part_two(&self) -> Result<Answer> {
+        arrangements(&self.runs).map(Answer::from)
     }
 }
 
-fn runs(mut numbers: Vec<usize>) -> Vec<usize> {
+/// Splits the chain from the outlet (0 jolts) into runs of adapters one jolt
+/// apart, returning the length of each run.
+fn runs(mut numbers: Vec<usize>) -> Result<Vec<usize>> {
+    numbers.push(0);
     numbers.sort_unstable();
 
     let mut runs = vec![];
 
-    let mut run = 1;
+    let mut run = 0;
     for i in 1..numbers.len() {
         let diff = numbers[i] - numbers[i - 1];
         if diff == 1 {
@@ -37,31 +53,43 @@ fn runs(mut numbers: Vec<usize>) -> Vec<usize> {
             runs.push(run + 1);
             run = 0;
         } else {
-            panic!(format!("diff != 1 or 3: {}", diff))
+            return Err(Error::no_solution(format!("diff != 1 or 3: {}", diff)));
         }
     }
     runs.push(run + 1);
 
-    runs
+    Ok(runs)
 }
 
-fn gaps(runs: &Vec<usize>) -> (usize, usize) {
+fn gaps(runs: &[usize]) -> (usize, usize) {
     let threes = runs.len();
     let ones = runs.iter().sum::<usize>() - threes;
     (ones, threes)
 }
 
-fn arrangements(runs: &Vec<usize>) -> usize {
-    let map = vec![0, 1, 1, 2, 4, 7]; // hard coded permutation map
-    runs.into_iter()
-        .map(|&run| map[run])
-        .fold(1, |acc, x| acc * x)
+fn arrangements(runs: &[usize]) -> Result<usize> {
+    runs.iter()
+        .try_fold(1usize, |acc, &run| acc.checked_mul(run_arrangements(run)?))
+        .ok_or_else(|| Error::no_solution("too many arrangements to count"))
+}
+
+/// The ways to pick adapters from a run of `len` consecutive joltages, keeping
+/// both ends: the tribonacci numbers 1, 1, 2, 4, 7, 13, ...
+fn run_arrangements(len: usize) -> Option<usize> {
+    let (mut a, mut b, mut c) = (0usize, 0usize, 1usize);
+    for _ in 1..len {
+        let next = a.checked_add(b)?.checked_add(c)?;
+        a = b;
+        b = c;
+        c = next;
+    }
+    Some(c)
 }
 
 #[test]
 fn test_runs_1() {
     let numbers = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
-    let runs = runs(numbers);
+    let runs = runs(numbers).unwrap();
     assert_eq!(runs, vec![2, 4, 3, 2, 1]);
 }
 
@@ -71,14 +99,14 @@ fn test_runs_2() {
         28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8,
         17, 7, 9, 4, 2, 34, 10, 3,
     ];
-    let runs = runs(numbers);
+    let runs = runs(numbers).unwrap();
     assert_eq!(runs, vec![5, 5, 1, 4, 3, 1, 5, 2, 1, 5]);
 }
 
 #[test]
 fn test_gaps_1() {
     let numbers = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
-    let (ones, threes) = gaps(&runs(numbers));
+    let (ones, threes) = gaps(&runs(numbers).unwrap());
     assert_eq!(ones, 7);
     assert_eq!(threes, 5)
 }
@@ -89,7 +117,7 @@ fn test_gaps_2() {
         28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8,
         17, 7, 9, 4, 2, 34, 10, 3,
     ];
-    let (ones, threes) = gaps(&runs(numbers));
+    let (ones, threes) = gaps(&runs(numbers).unwrap());
     assert_eq!(ones, 22);
     assert_eq!(threes, 10)
 }
@@ -97,7 +125,7 @@ fn test_gaps_2() {
 #[test]
 fn test_arrangements_1() {
     let numbers = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
-    let arrangements = arrangements(&runs(numbers));
+    let arrangements = arrangements(&runs(numbers).unwrap()).unwrap();
     assert_eq!(arrangements, 8);
 }
 
@@ -107,6 +135,6 @@ fn test_arrangements_2() {
         28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35, 8,
         17, 7, 9, 4, 2, 34, 10, 3,
     ];
-    let arrangements = arrangements(&runs(numbers));
+    let arrangements = arrangements(&runs(numbers).unwrap()).unwrap();
     assert_eq!(arrangements, 19208);
 }
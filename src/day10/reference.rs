@@ -0,0 +1,77 @@
+//! Naive versions of day 10 that work on the whole chain of adapters, to
+//! check the run-based solver against.
+
+use proptest::{collection::vec, prelude::*};
+
+use super::Day10;
+use crate::{
+    differential::{agree, solve},
+    Part,
+};
+
+/// The chain from the outlet through every adapter to the device.
+fn chain(adapters: &[usize]) -> Vec<usize> {
+    let mut chain = adapters.to_vec();
+    chain.push(0);
+    chain.sort_unstable();
+    chain.push(chain.last().unwrap() + 3);
+    chain
+}
+
+fn differences(adapters: &[usize]) -> usize {
+    let chain = chain(adapters);
+    let count = |diff| chain.windows(2).filter(|w| w[1] - w[0] == diff).count();
+    count(1) * count(3)
+}
+
+/// Counts the ways to reach each adapter from every adapter up to three jolts
+/// below it.
+fn arrangements(adapters: &[usize]) -> usize {
+    let chain = chain(adapters);
+    let mut ways = vec![0; chain.len()];
+    ways[0] = 1;
+    for i in 1..chain.len() {
+        ways[i] = (0..i)
+            .filter(|&j| chain[i] - chain[j] <= 3)
+            .map(|j| ways[j])
+            .sum();
+    }
+    *ways.last().unwrap()
+}
+
+/// Adapters one or three jolts apart, starting one or three jolts above the
+/// outlet.
+fn adapters() -> impl Strategy<Value = Vec<usize>> {
+    vec(prop_oneof![Just(1), Just(3)], 1..60).prop_map(|diffs| {
+        diffs
+            .iter()
+            .scan(0, |joltage, diff| {
+                *joltage += diff;
+                Some(*joltage)
+            })
+            .collect()
+    })
+}
+
+fn input(adapters: &[usize]) -> String {
+    let lines: Vec<String> = adapters.iter().rev().map(usize::to_string).collect();
+    lines.join("\n")
+}
+
+#[test]
+fn test_differences() {
+    agree(
+        adapters(),
+        |adapters| solve::<Day10>(&input(adapters), Part::One),
+        |adapters| Ok(differences(adapters).into()),
+    );
+}
+
+#[test]
+fn test_arrangements() {
+    agree(
+        adapters(),
+        |adapters| solve::<Day10>(&input(adapters), Part::Two),
+        |adapters| Ok(arrangements(adapters).into()),
+    );
+}
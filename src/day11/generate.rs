@@ -0,0 +1,62 @@
+use crate::{
+    generate::{Generated, Rng},
+    grid::{Grid, ADJACENT},
+    Part,
+};
+
+/// Runs the seating rules until nothing changes, returning the number of
+/// occupied seats, or `None` if the layout has not settled in a few rounds per
+/// row and column. Random layouts can flip between two states forever.
+/// `in_sight` looks past the floor to the first seat, as part two does.
+fn settle(seats: &Grid<u8>, threshold: usize, in_sight: bool) -> Option<usize> {
+    let limit = 4 * (seats.width() + seats.height());
+    let mut seats = seats.clone();
+    for _ in 0..limit {
+        let mut next = seats.clone();
+        for (point, &seat) in seats.iter() {
+            let around = ADJACENT
+                .iter()
+                .filter_map(|&direction| {
+                    let mut ray = seats.ray(point, direction);
+                    if in_sight {
+                        ray.find(|&(_, &s)| s != b'.')
+                    } else {
+                        ray.next()
+                    }
+                })
+                .filter(|&(_, &s)| s == b'#')
+                .count();
+            match seat {
+                b'L' if around == 0 => next[point] = b'#',
+                b'#' if around >= threshold => next[point] = b'L',
+                _ => {}
+            }
+        }
+        if next == seats {
+            return Some(seats.iter().filter(|&(_, &s)| s == b'#').count());
+        }
+        seats = next;
+    }
+    None
+}
+
+/// A seat layout of about `size` rows, mostly seats, that settles like a real
+/// input does. Part two's answer is left out if its rules never settle.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let height = size.clamp(2, 200);
+    loop {
+        let width = rng.range(2..height.max(3) + 1);
+        let cells = (0..width * height)
+            .map(|_| if rng.chance(1, 5) { b'.' } else { b'L' })
+            .collect();
+        let seats = Grid::new(width, cells);
+        if let Some(occupied) = settle(&seats, 4, false) {
+            let input = seats.display(|&seat| seat as char);
+            let generated = Generated::new(input.to_string()).answer(Part::One, occupied);
+            return match settle(&seats, 5, true) {
+                Some(occupied) => generated.answer(Part::Two, occupied),
+                None => generated,
+            };
+        }
+    }
+}
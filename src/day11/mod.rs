@@ -1,26 +1,68 @@
 use std::mem::swap;
 
+use serde::Deserialize;
+
+mod generate;
 mod parse;
 
-use crate::{Challenge, parsers::{all, grid}};
+use crate::config::Overrides;
+use crate::generate::{Generated, Rng};
+use crate::grid::{Grid, Point, ADJACENT};
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    parsers::all,
+    Challenge,
+};
 pub struct Day11 {
-    grid: Vec<Vec<Spot>>,
+    grid: Grid<Spot>,
+    params: Params,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// How many occupied neighbours make someone leave their seat.
+    pub threshold: usize,
+    /// How many occupied seats in sight make someone leave their seat in
+    /// part two.
+    pub visible_threshold: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            threshold: 4,
+            visible_threshold: 5,
+        }
+    }
 }
 
-impl Challenge for Day11 {
+impl Challenge<'_> for Day11 {
     fn name() -> &'static str {
         "day11"
     }
-    fn new(input: String) -> Self {
-        Day11 {
-            grid: all(grid(parse::spot)(&input)),
-        }
+    fn day() -> u8 {
+        11
+    }
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day11 {
+            grid: all(Grid::parser(parse::spot), input)?,
+            params: Params::default(),
+        })
+    }
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        self.params = overrides.apply()?;
+        Ok(())
     }
-    fn part_one(&self) -> usize {
-        run(self.grid.clone())
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_two(&self) -> usize {
-        0
+    fn part_one(&self) -> Result<Answer> {
+        run(self.grid.clone(), self.params.threshold, neighbours).map(Answer::from)
+    }
+    fn part_two(&self) -> Result<Answer> {
+        run(self.grid.clone(), self.params.visible_threshold, visible).map(Answer::from)
     }
 }
 
@@ -31,107 +73,66 @@ pub enum Spot {
     Fill,
 }
 
-fn neighbours(grid: &Vec<Vec<Spot>>, i: usize, j: usize) -> usize {
-    let mut count = 0;
-    let w = grid[0].len();
-    let h = grid.len();
-
-    let xrange = if i == 0 {
-        0..=1
-    } else if i + 1 == w {
-        (i - 1)..=i
-    } else {
-        (i - 1)..=(i + 1)
-    };
-    let yrange = if j == 0 {
-        0..=1
-    } else if j + 1 == h {
-        (j - 1)..=j
-    } else {
-        (j - 1)..=(j + 1)
-    };
-
-    for x in xrange.clone() {
-        for y in yrange.clone() {
-            if x == i && y == j {
-                continue;
-            }
-            count += if grid[y][x] == Spot::Fill { 1 } else { 0 };
-        }
-    }
-    count
+/// The number of occupied seats around `point`.
+fn neighbours(grid: &Grid<Spot>, point: Point) -> usize {
+    grid.neighbours(point, &ADJACENT)
+        .filter(|&(_, &spot)| spot == Spot::Fill)
+        .count()
 }
 
-fn step(grid: &Vec<Vec<Spot>>) -> Vec<Vec<Spot>> {
+/// The number of occupied seats seen from `point`, looking past the floor in
+/// each direction to the first seat.
+fn visible(grid: &Grid<Spot>, point: Point) -> usize {
+    ADJACENT
+        .iter()
+        .filter_map(|&direction| {
+            grid.ray(point, direction)
+                .find(|&(_, &spot)| spot != Spot::Floor)
+        })
+        .filter(|&(_, &spot)| spot == Spot::Fill)
+        .count()
+}
+
+/// Counts the occupied seats around a point, either next to it or in sight.
+type Around = fn(&Grid<Spot>, Point) -> usize;
+
+fn step(grid: &Grid<Spot>, threshold: usize, around: Around) -> Grid<Spot> {
     let mut output = grid.clone();
-    let w = grid[0].len();
-    let h = grid.len();
-
-    for i in 0..w {
-        for j in 0..h {
-            let neighbours = neighbours(grid, i, j);
-            if output[j][i] == Spot::Empty && neighbours == 0 {
-                output[j][i] = Spot::Fill;
-            } else if output[j][i] == Spot::Fill && neighbours >= 4 {
-                output[j][i] = Spot::Empty;
-            }
+
+    for (point, &spot) in grid.iter() {
+        let neighbours = around(grid, point);
+        if spot == Spot::Empty && neighbours == 0 {
+            output[point] = Spot::Fill;
+        } else if spot == Spot::Fill && neighbours >= threshold {
+            output[point] = Spot::Empty;
         }
     }
 
     output
 }
 
-fn count(grid: Vec<Vec<Spot>>) -> usize {
-    grid.into_iter()
-        .map(|row| {
-            row.into_iter()
-                .map(|spot| if spot == Spot::Fill { 1 } else { 0 })
-                .sum::<usize>()
-        })
-        .sum()
+fn count(grid: &Grid<Spot>) -> usize {
+    grid.iter().filter(|&(_, &spot)| spot == Spot::Fill).count()
 }
 
-fn run(mut grid: Vec<Vec<Spot>>) -> usize {
-    loop {
-        let mut output = step(&grid);
+/// Steps the layout until nothing changes, and counts the occupied seats.
+/// Some layouts flip between states forever, so this gives up after as many
+/// rounds as there are cells.
+fn run(mut grid: Grid<Spot>, threshold: usize, around: Around) -> Result<usize> {
+    let limit = grid.width() * grid.height() + 1;
+    for _ in 0..limit {
+        let mut output = step(&grid, threshold, around);
         if grid == output {
-            return count(grid);
+            return Ok(count(&grid));
         }
         swap(&mut grid, &mut output);
     }
+    Err(Error::no_solution(format!(
+        "the seats have not settled after {} rounds",
+        limit
+    )))
 }
 
-// fn neighbours2(grid: &Vec<Vec<Spot>>, i: usize, j: usize) -> usize {
-//     let mut count = 0;
-//     let w = grid[0].len();
-//     let h = grid.len();
-
-//     let xrange = if i == 0 {
-//         0..=1
-//     } else if i + 1 == w {
-//         (i - 1)..=i
-//     } else {
-//         (i - 1)..=(i + 1)
-//     };
-//     let yrange = if j == 0 {
-//         0..=1
-//     } else if j + 1 == h {
-//         (j - 1)..=j
-//     } else {
-//         (j - 1)..=(j + 1)
-//     };
-
-//     for x in xrange.clone() {
-//         for y in yrange.clone() {
-//             if x == i && y == j {
-//                 continue;
-//             }
-//             count += if grid[y][x] == Spot::Fill { 1 } else { 0 };
-//         }
-//     }
-//     count
-// }
-
 #[test]
 fn test_neighbours() {
     let input = "#.##.L#.##
@@ -144,9 +145,9 @@ L.#.#..#..
 #L######L#
 #.LL###L.L
 #.#L###.##";
-    let (_, grid) = grid(parse::spot)(input).unwrap();
-    assert_eq!(neighbours(&grid, 0, 0), 1);
-    assert_eq!(neighbours(&grid, 2, 0), 3);
+    let grid = all(Grid::parser(parse::spot), input).unwrap();
+    assert_eq!(neighbours(&grid, (0, 0)), 1);
+    assert_eq!(neighbours(&grid, (2, 0)), 3);
 }
 
 #[test]
@@ -172,10 +173,10 @@ L.L.L..#..
 #.LLLLLL.L
 #.#L#L#.##";
 
-    let (_, g) = grid(parse::spot)(input).unwrap();
-    let (_, expected) = grid(parse::spot)(expected).unwrap();
+    let grid = all(Grid::parser(parse::spot), input).unwrap();
+    let expected = all(Grid::parser(parse::spot), expected).unwrap();
 
-    assert_eq!(step(&g), expected);
+    assert_eq!(step(&grid, 4, neighbours), expected);
 }
 
 #[test]
@@ -190,7 +191,36 @@ L.LLLLL.LL
 LLLLLLLLLL
 L.LLLLLL.L
 L.LLLLL.LL";
-    let (_, grid) = grid(parse::spot)(input).unwrap();
-    let count = run(grid);
-    assert_eq!(count, 37);
+    let grid = all(Grid::parser(parse::spot), input).unwrap();
+    assert_eq!(run(grid.clone(), 4, neighbours).unwrap(), 37);
+    assert_eq!(run(grid, 5, visible).unwrap(), 26);
+}
+
+#[test]
+fn test_visible() {
+    let input = ".......#.
+...#.....
+.#.......
+.........
+..#L....#
+....#....
+.........
+#........
+...#.....";
+    let grid = all(Grid::parser(parse::spot), input).unwrap();
+    assert_eq!(visible(&grid, (3, 4)), 8);
+
+    let grid = all(Grid::parser(parse::spot), ".L.L.#.#.#.#.").unwrap();
+    assert_eq!(visible(&grid, (1, 0)), 0);
+}
+
+#[test]
+fn test_oscillating() {
+    // leaving over one neighbour, two seats side by side fill and empty forever
+    let grid = all(Grid::parser(parse::spot), "LL").unwrap();
+    assert_eq!(run(grid.clone(), 2, neighbours).unwrap(), 2);
+    assert!(matches!(
+        run(grid, 1, neighbours),
+        Err(Error::NoSolution(_))
+    ));
 }
@@ -1,12 +1,8 @@
 use super::Spot;
-use nom::{branch::alt, character::complete::char, combinator::value, IResult};
+use crate::parsers::{from_char, IResult};
 
 pub fn spot(input: &str) -> IResult<&str, Spot> {
-    alt((
-        value(Spot::Floor, char('.')),
-        value(Spot::Empty, char('L')),
-        value(Spot::Fill, char('#')),
-    ))(input)
+    from_char(&[('.', Spot::Floor), ('L', Spot::Empty), ('#', Spot::Fill)])(input)
 }
 
 #[test]
@@ -0,0 +1,52 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+/// Turns `(x, y)` a quarter turn anticlockwise `turns` times.
+fn rotate((x, y): (isize, isize), turns: isize) -> (isize, isize) {
+    (0..turns.rem_euclid(4)).fold((x, y), |(x, y), _| (-y, x))
+}
+
+/// `size` random navigation instructions, with turns in multiples of 90°.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut ship = (0, 0);
+    let mut facing = (1, 0);
+    let (mut ship2, mut waypoint) = ((0, 0), (10, 1));
+
+    let mut lines = vec![];
+    for _ in 0..size.max(1) {
+        let action = *rng.pick(b"NESWLRF") as char;
+        let value = if action == 'L' || action == 'R' {
+            90 * rng.signed(1..4)
+        } else {
+            rng.signed(1..100)
+        };
+        let direction = match action {
+            'N' => Some((0, 1)),
+            'E' => Some((1, 0)),
+            'S' => Some((0, -1)),
+            'W' => Some((-1, 0)),
+            _ => None,
+        };
+        match (action, direction) {
+            (_, Some((dx, dy))) => {
+                ship = (ship.0 + dx * value, ship.1 + dy * value);
+                waypoint = (waypoint.0 + dx * value, waypoint.1 + dy * value);
+            }
+            ('L', _) | ('R', _) => {
+                let turns = if action == 'L' { value / 90 } else { -value / 90 };
+                facing = rotate(facing, turns);
+                waypoint = rotate(waypoint, turns);
+            }
+            _ => {
+                ship = (ship.0 + facing.0 * value, ship.1 + facing.1 * value);
+                ship2 = (ship2.0 + waypoint.0 * value, ship2.1 + waypoint.1 * value);
+            }
+        }
+        lines.push(format!("{}{}", action, value));
+    }
+    Generated::new(lines.join("\n"))
+        .answer(Part::One, ship.0.abs() + ship.1.abs())
+        .answer(Part::Two, ship2.0.abs() + ship2.1.abs())
+}
@@ -1,37 +1,66 @@
 use parse::Step;
+use serde::Deserialize;
 
+mod generate;
 mod parse;
-use crate::{Challenge, parsers::{all, lines}};
+use crate::config::Overrides;
+use crate::generate::{Generated, Rng};
+use crate::{Challenge, answer::Answer, error::Result, parsers::{all, lines}};
 
 pub struct Day12 {
     steps: Vec<Step>,
+    params: Params,
 }
 
-impl Challenge for Day12 {
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Params {
+    /// Where the waypoint starts in part two, relative to the ship.
+    pub waypoint: (isize, isize),
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { waypoint: (10, 1) }
+    }
+}
+
+impl Challenge<'_> for Day12 {
     fn name() -> &'static str {
         "day12"
     }
-    fn new(input: String) -> Self {
-        Day12 {
-            steps: all(lines(parse::step)(&input)),
-        }
+    fn day() -> u8 {
+        12
+    }
+    fn new(input: &str) -> Result<Self> {
+        Ok(Day12 {
+            steps: all(lines(parse::step), input)?,
+            params: Params::default(),
+        })
+    }
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        self.params = overrides.apply()?;
+        Ok(())
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_one(&self) -> usize {
+    fn part_one(&self) -> Result<Answer> {
         let start = State {
             dir: Dir::East,
             x: 0,
             y: 0,
         };
         let end = self.steps.iter().fold(start, apply_step);
-        (end.x.abs() + end.y.abs()) as usize
+        Ok((end.x.abs() + end.y.abs()).into())
     }
-    fn part_two(&self) -> usize {
+    fn part_two(&self) -> Result<Answer> {
         let start = State2 {
-            waypoint: Point(10, 1),
+            waypoint: Point(self.params.waypoint.0, self.params.waypoint.1),
             ship: Point(0, 0),
         };
         let end = self.steps.iter().fold(start, apply_step2);
-        (end.ship.0.abs() + end.ship.1.abs()) as usize
+        Ok((end.ship.0.abs() + end.ship.1.abs()).into())
     }
 }
 
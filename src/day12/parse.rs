@@ -1,6 +1,6 @@
-use nom::{branch::alt, character::complete::char, combinator::map, sequence::preceded, IResult};
+use nom::{branch::alt, character::complete::char, combinator::map, sequence::preceded};
 
-use crate::parsers::number;
+use crate::parsers::{number, IResult};
 
 pub enum Step {
     North(isize),
@@ -15,12 +15,12 @@ pub enum Step {
 pub fn step(input: &str) -> IResult<&str, Step> {
     use Step::*;
     alt((
-        map(preceded(char('N'), number), |x| North(x)),
-        map(preceded(char('E'), number), |x| East(x)),
-        map(preceded(char('S'), number), |x| South(x)),
-        map(preceded(char('W'), number), |x| West(x)),
-        map(preceded(char('L'), number), |x| Left(x)),
-        map(preceded(char('R'), number), |x| Right(x)),
-        map(preceded(char('F'), number), |x| Forward(x)),
+        map(preceded(char('N'), number), North),
+        map(preceded(char('E'), number), East),
+        map(preceded(char('S'), number), South),
+        map(preceded(char('W'), number), West),
+        map(preceded(char('L'), number), Left),
+        map(preceded(char('R'), number), Right),
+        map(preceded(char('F'), number), Forward),
     ))(input)
 }
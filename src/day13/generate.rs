@@ -0,0 +1,80 @@
+use crate::{
+    generate::{Generated, Rng},
+    Part,
+};
+
+/// The primes below `limit`, by the sieve of Eratosthenes.
+fn primes(limit: usize) -> Vec<u128> {
+    let mut composite = vec![false; limit];
+    for n in 2..limit {
+        if !composite[n] {
+            for multiple in (n * n..limit).step_by(n) {
+                composite[multiple] = true;
+            }
+        }
+    }
+    (2..limit)
+        .filter(|&n| !composite[n])
+        .map(|n| n as u128)
+        .collect()
+}
+
+/// How long after `time` the bus `id` next leaves.
+fn wait_at(time: u128, id: u128) -> u128 {
+    (id - time % id) % id
+}
+
+/// A schedule of up to `size` buses (at most 9, as in real inputs) whose IDs
+/// are distinct primes, so they are coprime. The IDs are all larger than the
+/// schedule is long and their product fits in a `u64`.
+pub fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let buses = size.clamp(2, 9);
+    let slots = buses * rng.range(2..8);
+    let mut primes: Vec<u128> = primes(1000);
+    primes.retain(|&id| id > slots as u128);
+    rng.shuffle(&mut primes);
+
+    // the first slot always has a bus, the rest are spread over the others
+    let mut offsets: Vec<usize> = (1..slots).collect();
+    rng.shuffle(&mut offsets);
+    offsets.truncate(buses - 1);
+    offsets.push(0);
+    offsets.sort_unstable();
+
+    let mut product: u128 = 1;
+    let mut schedule: Vec<Option<u128>> = vec![None; slots];
+    for (&offset, &id) in offsets.iter().zip(&primes) {
+        if product * id > u64::MAX as u128 {
+            break;
+        }
+        product *= id;
+        schedule[offset] = Some(id);
+    }
+
+    let time = rng.range(100_000..1_000_000) as u128;
+    let (wait, id) = schedule
+        .iter()
+        .flatten()
+        .map(|&id| (wait_at(time, id), id))
+        .min_by_key(|&(wait, _)| wait)
+        .unwrap();
+
+    // sieve for the timestamp one bus at a time
+    let (mut timestamp, mut step) = (0, 1);
+    for (offset, id) in schedule.iter().enumerate() {
+        if let Some(id) = id {
+            while wait_at(timestamp + offset as u128, *id) > 0 {
+                timestamp += step;
+            }
+            step *= id;
+        }
+    }
+
+    let schedule: Vec<String> = schedule
+        .iter()
+        .map(|id| id.map_or("x".to_string(), |id| id.to_string()))
+        .collect();
+    Generated::new(format!("{}\n{}", time, schedule.join(",")))
+        .answer(Part::One, id * wait)
+        .answer(Part::Two, timestamp)
+}
@@ -1,37 +1,55 @@
+mod generate;
 mod parse;
-use crate::{parsers::all, Challenge};
+use crate::{
+    answer::Answer,
+    error::{Error, Result},
+    generate::{Generated, Rng},
+    parsers::all,
+    Challenge,
+};
 
 pub struct Day13 {
     time: u128,
     buses: Vec<Option<u128>>,
 }
 
-impl Challenge for Day13 {
+impl Challenge<'_> for Day13 {
     fn name() -> &'static str {
         "day13"
     }
-    fn new(input: String) -> Self {
-        let (time, buses) = all(parse::input(&input));
-        Day13 { time, buses }
+    fn day() -> u8 {
+        13
     }
-    fn part_one(&self) -> usize {
-        let (earliest_time, bus) = find_earliest_bus(self.time, &self.buses);
-        (bus * (earliest_time - self.time)) as usize
+    fn new(input: &str) -> Result<Self> {
+        let (time, buses) = all(parse::input, input)?;
+        if buses.iter().all(Option::is_none) {
+            return Err(Error::no_solution("no buses are in service"));
+        }
+        Ok(Day13 { time, buses })
+    }
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate::generate(rng, size))
     }
-    fn part_two(&self) -> usize {
-        find_earliest_series(&self.buses) as usize
+    fn part_one(&self) -> Result<Answer> {
+        let (wait, bus) = find_earliest_bus(self.time, &self.buses)
+            .ok_or_else(|| Error::no_solution("no buses are in service"))?;
+        let answer = bus
+            .checked_mul(wait)
+            .ok_or_else(|| Error::no_solution("the answer is past 128 bits"))?;
+        Ok(answer.into())
+    }
+    fn part_two(&self) -> Result<Answer> {
+        Ok(find_earliest_series(&self.buses)?.into())
     }
 }
 
-fn find_earliest_bus(mut time: u128, buses: &Vec<Option<u128>>) -> (u128, u128) {
-    loop {
-        for bus in buses.iter().filter_map(|&x| x) {
-            if time % bus == 0 {
-                return (time, bus);
-            }
-        }
-        time += 1;
-    }
+/// How long after `time` the first bus leaves, and its ID.
+fn find_earliest_bus(time: u128, buses: &[Option<u128>]) -> Option<(u128, u128)> {
+    buses
+        .iter()
+        .flatten()
+        .map(|&bus| ((bus - time % bus) % bus, bus))
+        .min_by_key(|&(wait, _)| wait)
 }
 
 #[test]
@@ -47,20 +65,47 @@ fn test_find_earliest_bus() {
         Some(31),
         Some(19),
     ];
-    let (time, bus) = find_earliest_bus(time, &buses);
-    assert_eq!(time, 944);
-    assert_eq!(bus, 59)
+    assert_eq!(find_earliest_bus(time, &buses), Some((5, 59)));
+    assert_eq!(find_earliest_bus(944, &buses), Some((0, 59)));
+    assert_eq!(
+        find_earliest_bus(939, &[Some((1 << 64) + 1)]),
+        Some(((1 << 64) - 938, (1 << 64) + 1))
+    );
 }
 
-fn find_earliest_series(buses: &Vec<Option<u128>>) -> u128 {
+fn find_earliest_series(buses: &[Option<u128>]) -> Result<u128> {
     let bus_orders: Vec<_> = buses
         .iter()
         .enumerate()
-        .filter_map(|(t, &bus)| bus.map(|bus| (t as u128, bus as u128)))
+        .filter_map(|(t, &bus)| bus.map(|bus| (t as u128, bus)))
         .collect();
 
+    // the theorem only gives one answer when the bus IDs share no factor
+    for (i, &(_, a)) in bus_orders.iter().enumerate() {
+        for &(_, b) in &bus_orders[i + 1..] {
+            if gcd(a, b) != 1 {
+                return Err(Error::no_solution(format!(
+                    "buses {} and {} share a factor, so their departures may never line up",
+                    a, b
+                )));
+            }
+        }
+    }
+    bus_orders
+        .iter()
+        .try_fold(1u128, |n, &(_, bus)| n.checked_mul(bus))
+        .ok_or_else(|| Error::no_solution("the bus IDs multiply past 128 bits"))?;
+
     let (a, n) = crt(&bus_orders);
-    n - (a % n)
+    Ok((n - a % n) % n)
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
 }
 
 // Chinese Remainder Theorem
@@ -70,18 +115,38 @@ fn crt(xs: &[(u128, u128)]) -> (u128, u128) {
     } else {
         let (a0, n0) = xs[0];
         let (a1, n1) = crt(&xs[1..]);
-        let (m0, m1) = ee(n0, n1, n0 * n1);
         let n = n0 * n1;
-        (mul(mul(a0, m1, n), n1, n) + mul(mul(a1, m0, n), n0, n), n)
+        let (m0, m1) = ee(n0, n1, n);
+        let x0 = mul(mul(a0, m1, n), n1, n);
+        let x1 = mul(mul(a1, m0, n), n0, n);
+        (add(x0, x1, n), n)
     }
 }
 
 fn mul(a: u128, b: u128, n: u128) -> u128 {
-    let a = a % n;
-    let b = b % n;
-    match a.checked_mul(b) {
-        Some(c) => c % n,
-        None => ((n - a) * (n - b)) % n,
+    let mut a = a % n;
+    let mut b = b % n;
+    if let Some(c) = a.checked_mul(b) {
+        return c % n;
+    }
+    // double and add, so nothing grows past n
+    let mut c = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            c = add(c, a, n);
+        }
+        a = add(a, a, n);
+        b >>= 1;
+    }
+    c
+}
+
+/// `a + b` modulo `n`, for `a` and `b` already below `n`.
+fn add(a: u128, b: u128, n: u128) -> u128 {
+    if a >= n - b {
+        a - (n - b)
+    } else {
+        a + b
     }
 }
 
@@ -89,9 +154,9 @@ fn subtract(a: u128, b: u128, n: u128) -> u128 {
     let a = a % n;
     let b = b % n;
     if b > a {
-        return (n - b) + a;
+        (n - b) + a
     } else {
-        return a - b;
+        a - b
     }
 }
 
@@ -102,9 +167,9 @@ fn ee(a: u128, b: u128, n: u128) -> (u128, u128) {
     let mut r: (u128, u128) = (a, b);
     while r.1 != 0 {
         let q = r.0 / r.1;
-        r = (r.1, subtract(r.0, q * r.1, n));
-        s = (s.1, subtract(s.0, q * s.1, n));
-        t = (t.1, subtract(t.0, q * t.1, n));
+        r = (r.1, r.0 - q * r.1);
+        s = (s.1, subtract(s.0, mul(q, s.1, n), n));
+        t = (t.1, subtract(t.0, mul(q, t.1, n), n));
     }
     (s.0, t.0)
 }
@@ -118,11 +183,36 @@ fn test_find_earliest_series() {
     // 1789,37,47,1889 first occurs at timestamp 1202161486.
 
     let buses = vec![Some(17), None, Some(13), Some(19)];
-    let time = find_earliest_series(&buses);
+    let time = find_earliest_series(&buses).unwrap();
     assert_eq!(time, 3417);
 
     let buses = vec![Some(1789), Some(37), Some(47), Some(1889)];
-    let time = find_earliest_series(&buses);
+    let time = find_earliest_series(&buses).unwrap();
     assert_eq!(time, 1202161486);
+
+    let buses = vec![Some(4), None, Some(6)];
+    assert!(matches!(
+        find_earliest_series(&buses),
+        Err(Error::NoSolution(_))
+    ));
+    let big = (1u128 << 70) + 1;
+    let buses = vec![Some(big), Some(big + 2)];
+    assert!(matches!(
+        find_earliest_series(&buses),
+        Err(Error::NoSolution(_))
+    ));
+
+    // coprime, with a product past 2^127
+    let buses = vec![Some((1 << 64) + 1), Some((1 << 63) + 1)];
+    assert_eq!(find_earliest_series(&buses).unwrap(), (1 << 64) + 1);
 }
 
+#[test]
+fn test_mul() {
+    let n = u128::MAX - 2;
+    assert_eq!(mul(n - 1, n - 1, n), 1);
+    assert_eq!(mul(n - 1, 2, n), n - 2);
+    // 2^200 = 2^127 * 2^73 = -2^73 (mod 2^127 + 1)
+    let n = (1 << 127) + 1;
+    assert_eq!(mul(1 << 100, 1 << 100, n), n - (1 << 73));
+}
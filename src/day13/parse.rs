@@ -1,22 +1,29 @@
 use nom::{
     branch::alt,
     character::complete::{char, line_ending},
-    combinator::{map, value},
-    multi::separated_list1,
+    combinator::{cut, map, value, verify},
+    error::context,
     sequence::separated_pair,
-    IResult,
 };
 
-use crate::parsers::number;
+use crate::parsers::{comma_list, number, IResult};
 
 pub fn bus(input: &str) -> IResult<&str, Option<u128>> {
-    alt((value(None, char('x')), map(number, |x| Some(x))))(input)
+    let id = verify(number, |&id: &u128| id != 0);
+    let id = context("bus ID", cut(id));
+    alt((value(None, char('x')), map(id, Some)))(input)
 }
 
 pub fn buses(input: &str) -> IResult<&str, Vec<Option<u128>>> {
-    separated_list1(char(','), bus)(input)
+    comma_list(bus)(input)
 }
 
 pub fn input(input: &str) -> IResult<&str, (u128, Vec<Option<u128>>)> {
     separated_pair(number, line_ending, buses)(input)
 }
+
+#[test]
+fn test_parse_zero_bus() {
+    assert!(crate::parsers::all(input, "939\n7,13,x").is_ok());
+    assert!(crate::parsers::all(input, "939\n7,0,x").is_err());
+}
@@ -0,0 +1,64 @@
+//! Differential tests pair a day's optimised solver with a naive reference
+//! implementation and check that they agree on random inputs.
+//!
+//! Inputs are drawn from a [`proptest`] strategy over the puzzle's structure,
+//! such as a list of joltage differences, rather than over raw text, so a
+//! disagreement shrinks to the smallest structure that still shows it.
+
+use std::fmt::Debug;
+
+use proptest::{
+    prop_assert_eq,
+    strategy::Strategy,
+    test_runner::{Config, TestRunner},
+};
+
+use crate::{answer::Answer, Part, Solver};
+
+/// The number of inputs tried per property.
+const CASES: u32 = 256;
+
+/// Checks that `fast` and `reference` give the same output on every input
+/// drawn from `strategy`, panicking with a minimal failing input if not.
+pub fn agree<S, O>(strategy: S, fast: impl Fn(&S::Value) -> O, reference: impl Fn(&S::Value) -> O)
+where
+    S: Strategy,
+    S::Value: Debug,
+    O: PartialEq + Debug,
+{
+    let mut runner = TestRunner::new(Config {
+        cases: CASES,
+        failure_persistence: None,
+        ..Config::default()
+    });
+    let result = runner.run(&strategy, |input| {
+        prop_assert_eq!(
+            fast(&input),
+            reference(&input),
+            "fast (left) and reference (right) disagree"
+        );
+        Ok(())
+    });
+    if let Err(err) = result {
+        panic!("{}", err);
+    }
+}
+
+/// Solves one part of `input` with the whole challenge, errors included.
+pub fn solve<S: Solver>(input: &str, part: Part) -> Result<Answer, String> {
+    use crate::Challenge;
+
+    let challenge = <S::For<'_>>::new(input).map_err(|err| err.to_string())?;
+    challenge.part(part).map_err(|err| err.to_string())
+}
+
+#[test]
+fn test_agree() {
+    agree(0..100u32, |&n| n * 2, |&n| n + n);
+}
+
+#[test]
+#[should_panic(expected = "minimal failing input: 11")]
+fn test_agree_shrinks() {
+    agree(0..100u32, |&n| n.min(10), |&n| n);
+}
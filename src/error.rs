@@ -0,0 +1,208 @@
+use std::{fmt, io, path::PathBuf};
+
+use nom::error::{ErrorKind, VerboseError, VerboseErrorKind};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// A file could not be read or written.
+    Io { path: PathBuf, source: io::Error },
+    /// No file was found for a named input.
+    MissingInput {
+        name: String,
+        searched: Vec<PathBuf>,
+    },
+    /// The input could not be parsed. `line` and `column` are 1-based, and
+    /// `text` is the line they point into.
+    Parse {
+        line: usize,
+        column: usize,
+        kind: ErrorKind,
+        /// The innermost [`nom::error::context`] the failure happened in.
+        context: Option<&'static str>,
+        text: String,
+    },
+    /// The input cache is damaged.
+    Cache { path: PathBuf, message: String },
+    /// An input could not be downloaded.
+    Fetch { url: String, message: String },
+    /// The input parsed, but the puzzle has no answer for it.
+    NoSolution(String),
+    /// A challenge's parameters could not be read or do not fit it.
+    Params(String),
+}
+
+impl Error {
+    /// Converts a nom error into a [`Error::Parse`], locating the failure within `input`.
+    pub fn parse(input: &str, err: nom::Err<VerboseError<&str>>) -> Self {
+        let errors = match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.errors,
+            nom::Err::Incomplete(_) => vec![("", VerboseErrorKind::Nom(ErrorKind::Complete))],
+        };
+        // the first error is where parsing stopped, the rest are the parsers it was inside
+        let remaining = errors.first().map_or("", |(remaining, _)| remaining);
+        let kind = errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Nom(kind) => Some(*kind),
+            VerboseErrorKind::Char(_) => Some(ErrorKind::Char),
+            VerboseErrorKind::Context(_) => None,
+        });
+        let context = errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Context(context) => Some(*context),
+            _ => None,
+        });
+        Error::at(input, remaining, kind.unwrap_or(ErrorKind::Verify), context)
+    }
+
+    /// A [`Error::Parse`] at the start of `remaining`, a suffix of `input`.
+    pub fn at(
+        input: &str,
+        remaining: &str,
+        kind: ErrorKind,
+        context: Option<&'static str>,
+    ) -> Self {
+        let (line, column) = position(input, remaining);
+        let text = input.lines().nth(line - 1).unwrap_or_default();
+        Error::Parse {
+            line,
+            column,
+            kind,
+            context,
+            text: text.trim_end_matches('\r').to_string(),
+        }
+    }
+
+    /// Locates the first byte of `input` that is not valid UTF-8 as a [`Error::Parse`].
+    pub fn utf8(input: &[u8], err: std::str::Utf8Error) -> Self {
+        let valid = std::str::from_utf8(&input[..err.valid_up_to()]).unwrap();
+        Error::at(valid, "", ErrorKind::Char, None)
+    }
+
+    pub fn no_solution<S: Into<String>>(message: S) -> Self {
+        Error::NoSolution(message.into())
+    }
+}
+
+/// Finds the 1-based line and column at which `remaining` starts within `input`.
+fn position(input: &str, remaining: &str) -> (usize, usize) {
+    let offset = input.len().saturating_sub(remaining.len());
+    let consumed = &input[..offset];
+    let line = consumed.matches('\n').count() + 1;
+    let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+    let column = consumed[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            Error::MissingInput { name, searched } => {
+                write!(f, "no '{}' input found, searched:", name)?;
+                for path in searched {
+                    write!(f, "\n    {}", path.display())?;
+                }
+                Ok(())
+            }
+            Error::Parse {
+                line,
+                column,
+                kind,
+                context,
+                text,
+            } => {
+                write!(f, "parse error at line {}, column {}", line, column)?;
+                if let Some(context) = context {
+                    write!(f, " in {}", context)?;
+                }
+                match kind {
+                    ErrorKind::Eof => write!(f, ": unexpected input")?,
+                    kind => write!(f, ": {}", kind.description())?,
+                }
+                // point at the column, keeping any tabs so the caret lines up
+                let indent: String = text
+                    .chars()
+                    .take(column - 1)
+                    .map(|c| if c == '\t' { '\t' } else { ' ' })
+                    .collect();
+                write!(f, "\n    {}\n    {}^", text, indent)
+            }
+            Error::Cache { path, message } => {
+                write!(f, "bad input cache {}: {}", path.display(), message)
+            }
+            Error::Fetch { url, message } => write!(f, "could not fetch {}: {}", url, message),
+            Error::NoSolution(message) => write!(f, "no solution: {}", message),
+            Error::Params(message) => write!(f, "bad parameters: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_position() {
+    let input = "12\n34\n5x6";
+    assert_eq!(position(input, input), (1, 1));
+    assert_eq!(position(input, &input[3..]), (2, 1));
+    assert_eq!(position(input, &input[7..]), (3, 2));
+    assert_eq!(position(input, ""), (3, 4));
+}
+
+#[test]
+fn test_utf8_error() {
+    let input = b"12\n3\xff4".to_vec();
+    let err = std::str::from_utf8(&input).unwrap_err();
+    match Error::utf8(&input, err) {
+        Error::Parse {
+            line,
+            column,
+            kind,
+            text,
+            ..
+        } => {
+            assert_eq!((line, column), (2, 2));
+            assert_eq!(kind, ErrorKind::Char);
+            assert_eq!(text, "3");
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn test_parse_error() {
+    use crate::parsers::number;
+
+    let input = "2020";
+    let err = Error::parse(input, number::<u8>(input).unwrap_err());
+    match err {
+        Error::Parse {
+            line, column, kind, ..
+        } => {
+            assert_eq!((line, column), (1, 1));
+            assert_eq!(kind, ErrorKind::MapRes);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+}
+
+#[test]
+fn test_parse_diagnostic() {
+    use crate::parsers::number;
+    use nom::{character::complete::char, error::context, sequence::preceded};
+
+    let input = "12\n\tx";
+    let err = preceded(char('\t'), context("number", number::<u8>))(&input[3..]).unwrap_err();
+    assert_eq!(
+        Error::parse(input, err).to_string(),
+        "parse error at line 2, column 2 in number: Digit\n    \tx\n    \t^"
+    );
+}
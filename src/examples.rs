@@ -0,0 +1,109 @@
+use crate::{
+    check::{sections, Expected},
+    config::Overrides,
+    error::{Error, Result},
+    Part,
+};
+
+/// A worked example from a day's puzzle statement.
+///
+/// Each day may keep its examples in `src/<day>/examples.txt`:
+///
+/// ```text
+/// [example 1]
+/// 1721
+/// 979
+/// [part one]
+/// 514579
+/// ```
+///
+/// Every `[example NAME]` section holds an input and is followed by the
+/// answers it should give. A part without an answer is not checked. An example
+/// made for other parameters than the puzzle's gives them in TOML under
+/// `[params]`, such as `preamble = 5` for day 9. The build script turns each
+/// example into a test that runs the whole challenge.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub name: String,
+    pub input: String,
+    pub expected: Expected,
+    pub params: Overrides,
+}
+
+pub fn parse(contents: &str) -> Result<Vec<Example>> {
+    let mut examples: Vec<Example> = vec![];
+    for section in sections(contents)? {
+        if let Some(name) = section.header.strip_prefix("example ") {
+            examples.push(Example {
+                name: name.trim().to_string(),
+                input: section.lines.join("\n").trim_matches('\n').to_string(),
+                expected: Expected::default(),
+                params: Overrides::default(),
+            });
+            continue;
+        }
+        if section.header == "params" {
+            let example = examples.last_mut().ok_or_else(|| section.error())?;
+            example.params = section
+                .lines
+                .join("\n")
+                .parse()
+                .map_err(|err| Error::Params(format!("example {}: {}", example.name, err)))?;
+            continue;
+        }
+        let part = section.part()?;
+        match examples.last_mut() {
+            Some(example) => example.expected.set(part, &section.lines),
+            None => return Err(section.error()),
+        }
+    }
+    Ok(examples)
+}
+
+/// Runs the example `name` of `day` through its challenge, panicking if any
+/// answer differs from the expected one. The example is also run as it would
+/// be saved on Windows, with `\r\n` line endings and a final newline.
+pub fn test(day: &str, name: &str, contents: &str) {
+    let examples = parse(contents).unwrap();
+    let example = examples.iter().find(|e| e.name == name).unwrap();
+    let crlf = example.input.replace('\n', "\r\n") + "\r\n";
+
+    let registry = crate::registry::challenges();
+    for input in [&example.input, &crlf].iter() {
+        let challenge = registry.get(day).unwrap().parse(input, &example.params);
+        let challenge = challenge.unwrap_or_else(|err| panic!("{} example {}: {}", day, name, err));
+        for &part in Part::ALL.iter() {
+            if let Some(expected) = example.expected.get(part) {
+                let answer = challenge.part(part).unwrap_or_else(|err| {
+                    panic!("{} example {} part {}: {}", day, name, part, err)
+                });
+                assert_eq!(&answer, expected, "{} example {} part {}", day, name, part);
+            }
+        }
+    }
+}
+
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+}
+
+#[test]
+fn test_parse() {
+    let examples =
+        parse("[example 1]\n1\n\n2\n\n[part one]\n3\n[example larger]\n4\n[part two]\n5\n")
+            .unwrap();
+    assert_eq!(examples.len(), 2);
+    assert_eq!(examples[0].name, "1");
+    assert_eq!(examples[0].input, "1\n\n2");
+    assert_eq!(examples[0].expected.get(Part::One).unwrap(), "3");
+    assert_eq!(examples[0].expected.get(Part::Two), None);
+    assert_eq!(examples[1].name, "larger");
+    assert_eq!(examples[1].expected.get(Part::Two).unwrap(), "5");
+
+    let examples = parse("[example 1]\n1\n[params]\npreamble = 5\n[part one]\n3").unwrap();
+    assert_eq!(examples[0].params, "preamble = 5".parse().unwrap());
+    assert!(parse("[example 1]\n1\n[params]\npreamble =").is_err());
+
+    assert!(parse("[part one]\n3").is_err());
+    assert!(parse("[example 1]\n1\n[answer]\n3").is_err());
+}
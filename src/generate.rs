@@ -0,0 +1,123 @@
+use std::ops::Range;
+
+use crate::{answer::Answer, check::Expected, Part};
+
+/// The number of records generated when no size is given.
+pub const DEFAULT_SIZE: usize = 100;
+
+/// A small seeded pseudo-random number generator (SplitMix64), so a generated
+/// input can be recreated from its seed on any machine.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+
+    /// A signed number in `range`, which must not be empty.
+    pub fn signed(&mut self, range: Range<isize>) -> isize {
+        assert!(!range.is_empty(), "empty range {:?}", range);
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as isize
+    }
+
+    /// True `numerator` times in `denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.range(0..denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i + 1));
+        }
+    }
+}
+
+/// A generated input, with the answers the generator could cheaply work out.
+#[derive(Debug, Default)]
+pub struct Generated {
+    pub input: String,
+    pub expected: Expected,
+}
+
+impl Generated {
+    pub fn new(input: String) -> Self {
+        Generated {
+            input,
+            expected: Expected::default(),
+        }
+    }
+
+    pub fn answer<A: Into<Answer>>(mut self, part: Part, answer: A) -> Self {
+        self.expected.insert(part, answer.into());
+        self
+    }
+}
+
+#[test]
+fn test_rng() {
+    // the same seed always gives the same numbers
+    let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+    assert!((0..10).all(|_| a.next_u64() == b.next_u64()));
+    assert_ne!(Rng::new(8).next_u64(), Rng::new(7).next_u64());
+
+    let mut rng = Rng::new(7);
+    let mut counts = [0; 4];
+    for _ in 0..1000 {
+        counts[rng.range(3..7) - 3] += 1;
+    }
+    assert!(counts.iter().all(|&c| c > 200), "{:?}", counts);
+    assert!((0..1000).all(|_| (-2..2).contains(&rng.signed(-2..2))));
+
+    let mut items: Vec<usize> = (0..10).collect();
+    rng.shuffle(&mut items);
+    items.sort_unstable();
+    assert_eq!(items, (0..10).collect::<Vec<_>>());
+}
+
+/// Generates a few inputs for every day that has a generator, and checks the
+/// day's answers against the ones the generator knows.
+#[test]
+fn test_generated() {
+    let registry = crate::registry::challenges();
+    for day in registry.iter() {
+        for seed in 0..4 {
+            let mut rng = Rng::new(seed);
+            let Some(generated) = day.generate(&mut rng, 1 + seed as usize * 50) else {
+                continue;
+            };
+            let report = crate::check::check(
+                day,
+                generated.input.as_bytes(),
+                &Default::default(),
+                &generated.expected,
+            );
+            assert!(
+                !report.is_regression(),
+                "{} seed {}: {:?}\n{}",
+                day.name(),
+                seed,
+                report,
+                generated.input
+            );
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! A rectangular grid of cells, such as the forest of day 3 or the seats of
+//! day 11.
+//!
+//! Cells are stored row by row in one `Vec`. A [`Point`] is `(x, y)`, with `x`
+//! counting columns from the left and `y` rows from the top.
+
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use nom::{combinator::map_res, error::VerboseError, Parser};
+
+use crate::parsers::{grid, IResult};
+
+pub type Point = (usize, usize);
+
+/// A step from one cell to another, as `(dx, dy)`.
+pub type Direction = (isize, isize);
+
+/// The directions to a cell's eight neighbours, the four that share an edge
+/// with it first, so `&ADJACENT[..4]` gives just those.
+pub const ADJACENT: [Direction; 8] = [
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid `width` cells wide, filled row by row from `cells`.
+    ///
+    /// # Panics
+    ///
+    /// If `cells` does not fill whole rows.
+    pub fn new(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.chunks_exact(width).remainder().is_empty(),
+            "{} cells do not make rows {} wide",
+            cells.len(),
+            width
+        );
+        Grid {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// A grid from its rows, which must all be as wide as the first.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, String> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(format!(
+                "row {} has {} cells, but the first has {}",
+                y + 1,
+                row.len(),
+                width
+            ));
+        }
+        Ok(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses a grid of cells, one row per line, as [`grid`] does, and checks
+    /// that every row is as wide as the first.
+    pub fn parser<'a, F>(cell: F) -> impl FnMut(&'a str) -> IResult<&'a str, Self>
+    where
+        F: Parser<&'a str, T, VerboseError<&'a str>>,
+    {
+        map_res(grid(cell), Grid::from_rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Point) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    /// The cell at `point` in a grid that repeats forever in every direction.
+    pub fn wrapping(&self, (x, y): Point) -> &T {
+        &self[(x % self.width, y % self.height)]
+    }
+
+    /// The point one `direction` step from `point`, if that is on the grid.
+    pub fn step(&self, (x, y): Point, (dx, dy): Direction) -> Option<Point> {
+        let point = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.get(point).map(|_| point)
+    }
+
+    /// The cells from `start` to the edge of the grid, stepping `direction`
+    /// each time. A row is the line from `(0, y)` going `(1, 0)`, and a
+    /// column the line from `(x, 0)` going `(0, 1)`.
+    pub fn line(&self, start: Point, direction: Direction) -> impl Iterator<Item = (Point, &T)> {
+        let start = self.get(start).map(|_| start);
+        std::iter::successors(start, move |&point| self.step(point, direction))
+            .map(move |point| (point, &self[point]))
+    }
+
+    /// The cells seen looking from `from` in `direction`, nearest first.
+    pub fn ray(&self, from: Point, direction: Direction) -> impl Iterator<Item = (Point, &T)> {
+        self.line(from, direction).skip(1)
+    }
+
+    /// The cells next to `point` in each of `directions` that are on the grid.
+    pub fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        directions
+            .iter()
+            .filter_map(move |&direction| self.ray(point, direction).next())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Every cell with its point, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// Shows the grid one row per line, drawing each cell as `draw` does.
+    pub fn display<F>(&self, draw: F) -> Render<'_, T, F>
+    where
+        F: Fn(&T) -> char,
+    {
+        Render { grid: self, draw }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.get(point) {
+            Some(cell) => cell,
+            None => panic!(
+                "{:?} is outside a grid of {}x{}",
+                point, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Point) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "{:?} is outside a grid of {}x{}",
+            (x, y),
+            self.width,
+            self.height
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// A grid drawn one row per line, from [`Grid::display`].
+pub struct Render<'a, T, F> {
+    grid: &'a Grid<T>,
+    draw: F,
+}
+
+impl<T, F: Fn(&T) -> char> fmt::Display for Render<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.grid.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", (self.draw)(cell))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn example() -> Grid<u8> {
+    use crate::parsers::{all, from_char};
+
+    let digit = from_char(&[('1', 1), ('2', 2), ('3', 3), ('4', 4), ('5', 5), ('6', 6)]);
+    all(Grid::parser(digit), "123\n456").unwrap()
+}
+
+#[test]
+fn test_grid() {
+    let grid = example();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid, Grid::new(3, vec![1, 2, 3, 4, 5, 6]));
+    assert_eq!(grid[(2, 1)], 6);
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.get((0, 2)), None);
+    assert_eq!(*grid.wrapping((4, 3)), 5);
+    assert_eq!(grid.step((0, 0), (1, 1)), Some((1, 1)));
+    assert_eq!(grid.step((0, 0), (-1, 0)), None);
+    assert_eq!(grid.step((2, 1), (1, 0)), None);
+
+    let cells = |cells: Vec<(Point, &u8)>| cells.into_iter().map(|(_, &c)| c).collect::<Vec<_>>();
+    assert_eq!(cells(grid.line((0, 1), (1, 0)).collect()), vec![4, 5, 6]);
+    assert_eq!(cells(grid.line((2, 0), (0, 1)).collect()), vec![3, 6]);
+    assert_eq!(cells(grid.ray((0, 0), (1, 0)).collect()), vec![2, 3]);
+    assert_eq!(grid.ray((5, 5), (1, 0)).next(), None);
+    assert_eq!(
+        cells(grid.neighbours((1, 0), &ADJACENT).collect()),
+        vec![3, 5, 1, 6, 4]
+    );
+    assert_eq!(
+        cells(grid.neighbours((1, 0), &ADJACENT[..4]).collect()),
+        vec![3, 5, 1]
+    );
+    assert_eq!(
+        grid.rows().collect::<Vec<_>>(),
+        vec![&[1, 2, 3], &[4, 5, 6]]
+    );
+    assert_eq!(grid.iter().nth(4), Some(((1, 1), &5)));
+
+    let shown = grid.display(|&c| if c % 2 == 0 { '#' } else { '.' });
+    assert_eq!(shown.to_string(), ".#.\n#.#");
+}
+
+#[test]
+fn test_parse_grid() {
+    use crate::parsers::{all, from_char};
+
+    let spot = || from_char(&[('.', false), ('#', true)]);
+    assert!(all(Grid::parser(spot()), "#.\n.#").is_ok());
+    assert!(all(Grid::parser(spot()), "#.\n.#.").is_err());
+    assert!(Grid::from_rows(vec![vec![1], vec![2, 3]]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "(3, 0) is outside a grid of 3x2")]
+fn test_index_out_of_bounds() {
+    let mut grid = example();
+    grid[(3, 0)] = 0;
+}
@@ -0,0 +1,218 @@
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    str,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::Input;
+use crate::{
+    error::{Error, Result},
+    read_file, write_file,
+};
+
+/// Environment variable overriding the cache directory.
+pub const CACHE_VAR: &str = "AOC_CACHE";
+
+/// Where a cached input came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Origin {
+    File { path: PathBuf },
+    Stdin,
+    Url { url: String },
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Origin::File { path } => write!(f, "{}", path.display()),
+            Origin::Stdin => write!(f, "<stdin>"),
+            Origin::Url { url } => write!(f, "{}", url),
+        }
+    }
+}
+
+/// One input recorded in the cache's index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub day: String,
+    pub name: String,
+    /// Hex encoded SHA-256 of the contents, which is also where they are stored.
+    pub sha256: String,
+    pub bytes: usize,
+    pub origin: Origin,
+    /// Seconds since the Unix epoch.
+    pub imported: u64,
+}
+
+/// A content-addressed store of inputs.
+///
+/// Contents live in `objects/<sha256>` and `index.json` maps each day's named
+/// inputs to their contents and provenance, so the same input can be installed
+/// again on any machine.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+pub fn hash<B: AsRef<[u8]>>(contents: B) -> String {
+    Sha256::digest(contents.as_ref())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+impl Cache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Cache { dir: dir.into() }
+    }
+
+    /// Uses `dir` if given, then `$AOC_CACHE`, and otherwise `.inputs` in
+    /// this crate's directory.
+    pub fn from_env(dir: Option<PathBuf>) -> Self {
+        let dir = dir
+            .or_else(|| env::var_os(CACHE_VAR).map(PathBuf::from))
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join(".inputs"));
+        Cache::new(dir)
+    }
+
+    fn index(&self) -> PathBuf {
+        self.dir.join("index.json")
+    }
+
+    fn object(&self, sha256: &str) -> PathBuf {
+        self.dir.join("objects").join(sha256)
+    }
+
+    pub fn entries(&self) -> Result<Vec<Entry>> {
+        let path = self.index();
+        match read_file(&path) {
+            Ok(index) => serde_json::from_str(&index).map_err(|err| Error::Cache {
+                path,
+                message: err.to_string(),
+            }),
+            Err(Error::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => {
+                Ok(vec![])
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn get(&self, day: &str, name: &str) -> Result<Option<Entry>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .find(|e| e.day == day && e.name == name))
+    }
+
+    /// Stores `contents` as the input `name` of `day`, replacing any previous
+    /// entry with that name.
+    pub fn import(&self, day: &str, name: &str, contents: &str, origin: Origin) -> Result<Entry> {
+        let sha256 = hash(contents);
+        let object = self.object(&sha256);
+        if !object.is_file() {
+            write_file(&object, contents)?;
+        }
+
+        let entry = Entry {
+            day: day.to_string(),
+            name: name.to_string(),
+            sha256,
+            bytes: contents.len(),
+            origin,
+            imported: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        };
+
+        let mut entries = self.entries()?;
+        entries.retain(|e| !(e.day == day && e.name == name));
+        entries.push(entry.clone());
+        entries.sort_by(|a, b| (&a.day, &a.name).cmp(&(&b.day, &b.name)));
+        let index = serde_json::to_string_pretty(&entries).unwrap();
+        write_file(self.index(), &index)?;
+
+        Ok(entry)
+    }
+
+    /// Maps the contents of `entry` into memory, checking them against their
+    /// hash. Objects are written once under their hash and never changed, so
+    /// unlike the user's own files they are safe to map.
+    pub fn open(&self, entry: &Entry) -> Result<Input> {
+        let path = self.object(&entry.sha256);
+        let contents = Input::map(&path)?;
+        if hash(contents.as_bytes()) != entry.sha256 {
+            return Err(Error::Cache {
+                path,
+                message: "contents do not match their hash".to_string(),
+            });
+        }
+        Ok(contents)
+    }
+
+    /// Reads the contents of `entry`, checking them against their hash.
+    pub fn read(&self, entry: &Entry) -> Result<String> {
+        let contents = self.open(entry)?;
+        let bytes = contents.as_bytes();
+        str::from_utf8(bytes)
+            .map(str::to_string)
+            .map_err(|err| Error::utf8(bytes, err))
+    }
+
+    /// Writes `entry` to `<dir>/<day>/<name>.txt`, where the
+    /// [`Resolver`](super::Resolver) will find it.
+    pub fn install(&self, entry: &Entry, dir: &Path) -> Result<PathBuf> {
+        let contents = self.read(entry)?;
+        let path = dir.join(&entry.day).join(format!("{}.txt", entry.name));
+        write_file(&path, &contents)?;
+        Ok(path)
+    }
+}
+
+#[test]
+fn test_hash() {
+    assert_eq!(
+        hash("abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn test_cache() {
+    let root = env::temp_dir().join(format!("aoc-cache-{}", std::process::id()));
+    let cache = Cache::new(root.join("cache"));
+    assert_eq!(cache.entries().unwrap(), vec![]);
+
+    let origin = Origin::File {
+        path: PathBuf::from("example.txt"),
+    };
+    let entry = cache.import("day01", "example1", "1\n2", origin).unwrap();
+    assert_eq!(entry.bytes, 3);
+    assert_eq!(cache.read(&entry).unwrap(), "1\n2");
+    assert!(matches!(cache.open(&entry), Ok(Input::Mapped(_))));
+
+    // importing again under the same name replaces the entry
+    let entry = cache
+        .import("day01", "example1", "3\n4", Origin::Stdin)
+        .unwrap();
+    cache
+        .import("day01", "input", "3\n4", Origin::Stdin)
+        .unwrap();
+    let entries = cache.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(cache.get("day01", "example1").unwrap(), Some(entry.clone()));
+    assert_eq!(entries[0].sha256, entries[1].sha256);
+
+    let path = cache.install(&entry, &root.join("src")).unwrap();
+    assert_eq!(path, root.join("src").join("day01").join("example1.txt"));
+    assert_eq!(read_file(path).unwrap(), "3\n4");
+
+    std::fs::write(cache.object(&entry.sha256), "tampered").unwrap();
+    assert!(matches!(cache.read(&entry), Err(Error::Cache { .. })));
+
+    std::fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,304 @@
+use std::{
+    env,
+    io::{Read, Write},
+    net::{IpAddr, TcpStream},
+    str,
+    time::Duration,
+};
+
+use crate::error::{Error, Result};
+
+/// Environment variable naming the server inputs are fetched from. Only plain
+/// HTTP is spoken, and the session cookie is only sent to this machine, so
+/// the real site needs a local TLS proxy.
+pub const ENDPOINT_VAR: &str = "AOC_ENDPOINT";
+/// Environment variable holding the value of the `session` cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Downloads puzzle inputs from `<endpoint>/<year>/day/<day>/input`.
+#[derive(Debug, Clone)]
+pub struct Client {
+    endpoint: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new<E: Into<String>, S: Into<String>>(endpoint: E, session: S) -> Self {
+        Client {
+            endpoint: endpoint.into(),
+            session: session.into(),
+        }
+    }
+
+    /// Reads the endpoint and session from `$AOC_ENDPOINT` and `$AOC_SESSION`.
+    pub fn from_env() -> Result<Self> {
+        let endpoint = env::var(ENDPOINT_VAR).map_err(|_| Error::Fetch {
+            url: "inputs".to_string(),
+            message: format!("${} is not set", ENDPOINT_VAR),
+        })?;
+        match env::var(SESSION_VAR) {
+            Ok(session) => Ok(Client::new(endpoint, session)),
+            Err(_) => Err(Error::Fetch {
+                url: endpoint,
+                message: format!("${} is not set", SESSION_VAR),
+            }),
+        }
+    }
+
+    pub fn url(&self, year: u16, day: u8) -> String {
+        format!(
+            "{}/{}/day/{}/input",
+            self.endpoint.trim_end_matches('/'),
+            year,
+            day
+        )
+    }
+
+    pub fn fetch(&self, year: u16, day: u8) -> Result<String> {
+        let url = self.url(year, day);
+        get(&url, &self.session).map_err(|message| Error::Fetch { url, message })
+    }
+}
+
+fn get(url: &str, session: &str) -> std::result::Result<String, String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or("only http:// endpoints are supported")?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+    if !is_loopback(host) {
+        return Err(format!(
+            "refusing to send the session cookie over plain HTTP to {}, which is not this machine",
+            host
+        ));
+    }
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let mut stream = TcpStream::connect(&address).map_err(|err| err.to_string())?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .map_err(|err| err.to_string())?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nCookie: session={}\r\nUser-Agent: {}\r\nConnection: close\r\n\r\n",
+        path, host, session, USER_AGENT
+    )
+    .map_err(|err| err.to_string())?;
+
+    let mut response = vec![];
+    stream
+        .read_to_end(&mut response)
+        .map_err(|err| err.to_string())?;
+    let response = Response::parse(&response)?;
+    if response.status != 200 {
+        return Err(format!(
+            "server responded with {} {}",
+            response.status, response.reason
+        ));
+    }
+    String::from_utf8(response.body).map_err(|_| "response is not valid UTF-8".to_string())
+}
+
+/// Whether `host`, with or without a port, names this machine.
+fn is_loopback(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    reason: String,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn parse(bytes: &[u8]) -> std::result::Result<Self, String> {
+        let end = find(bytes, b"\r\n\r\n").ok_or("incomplete response")?;
+        let head = str::from_utf8(&bytes[..end]).map_err(|_| "malformed response headers")?;
+        let body = &bytes[end + 4..];
+
+        let mut lines = head.split("\r\n");
+        let mut status_line = lines.next().unwrap_or_default().splitn(3, ' ');
+        let status = status_line
+            .nth(1)
+            .and_then(|s| s.parse().ok())
+            .ok_or("malformed status line")?;
+        let reason = status_line.next().unwrap_or_default().to_string();
+
+        let mut chunked = false;
+        let mut length = None;
+        for line in lines {
+            let (name, value) = line.split_at(line.find(':').ok_or("malformed header")?);
+            let value = value[1..].trim();
+            if name.eq_ignore_ascii_case("transfer-encoding") {
+                chunked = value.eq_ignore_ascii_case("chunked");
+            } else if name.eq_ignore_ascii_case("content-length") {
+                length = Some(value.parse().map_err(|_| "malformed content length")?);
+            }
+        }
+
+        let body = if chunked {
+            dechunk(body)?
+        } else if let Some(length) = length {
+            body.get(..length).ok_or("truncated response")?.to_vec()
+        } else {
+            body.to_vec()
+        };
+        Ok(Response {
+            status,
+            reason,
+            body,
+        })
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Decodes a body sent with `Transfer-Encoding: chunked`.
+fn dechunk(mut body: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let mut output = vec![];
+    loop {
+        let end = find(body, b"\r\n").ok_or("truncated chunk")?;
+        let size = str::from_utf8(&body[..end]).map_err(|_| "malformed chunk size")?;
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| "malformed chunk size")?;
+        body = &body[end + 2..];
+        if size == 0 {
+            return Ok(output);
+        }
+        let chunk = body.get(..size).ok_or("truncated chunk")?;
+        output.extend_from_slice(chunk);
+        body = body.get(size + 2..).ok_or("truncated chunk")?;
+    }
+}
+
+#[test]
+fn test_parse_response() {
+    let response = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n1\n2\nextra";
+    assert_eq!(
+        Response::parse(response),
+        Ok(Response {
+            status: 200,
+            reason: "OK".to_string(),
+            body: b"1\n2\n".to_vec(),
+        })
+    );
+
+    let response = b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n2\r\n1\n\r\n3;x=y\r\n2\n3\r\n0\r\n\r\n";
+    assert_eq!(Response::parse(response).unwrap().body, b"1\n2\n3");
+
+    assert!(Response::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 9\r\n\r\n1\n2").is_err());
+    assert!(Response::parse(b"HTTP/1.1 200 OK\r\n").is_err());
+}
+
+#[cfg(test)]
+mod mock {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    /// A stand-in for the puzzle server, answering `requests` requests on a
+    /// local port. It only knows day 1 of 2020 and wants `session=secret`.
+    pub fn server(requests: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&stream);
+                let mut request = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                    request.push(line.trim_end().to_string());
+                }
+
+                let authorised = request.iter().any(|l| l == "Cookie: session=secret");
+                let response = match request[0].as_str() {
+                    _ if !authorised => "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n",
+                    "GET /2020/day/1/input HTTP/1.1" => {
+                        "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n1721\n\r\n4\r\n979\n\r\n0\r\n\r\n"
+                    }
+                    _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n",
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", address)
+    }
+}
+
+#[test]
+fn test_fetch() {
+    let endpoint = mock::server(3);
+
+    let client = Client::new(endpoint.as_str(), "secret");
+    assert_eq!(client.fetch(2020, 1).unwrap(), "1721\n979\n");
+    match client.fetch(2020, 2) {
+        Err(Error::Fetch { url, message }) => {
+            assert_eq!(url, format!("{}/2020/day/2/input", endpoint));
+            assert_eq!(message, "server responded with 404 Not Found");
+        }
+        other => panic!("expected a fetch error, got {:?}", other),
+    }
+    assert!(Client::new(endpoint, "wrong").fetch(2020, 1).is_err());
+
+    assert!(Client::new("https://adventofcode.com", "secret")
+        .fetch(2020, 1)
+        .is_err());
+}
+
+#[test]
+fn test_refuse_remote_http() {
+    // nothing is listening, so only the refusal can make this fail quickly
+    match Client::new("http://adventofcode.com", "secret").fetch(2020, 1) {
+        Err(Error::Fetch { message, .. }) => assert_eq!(
+            message,
+            "refusing to send the session cookie over plain HTTP to adventofcode.com, \
+             which is not this machine"
+        ),
+        other => panic!("expected a refusal, got {:?}", other),
+    }
+    assert!(Client::new("http://192.168.1.2:8080", "secret")
+        .fetch(2020, 1)
+        .is_err());
+
+    for host in &[
+        "localhost",
+        "localhost:8080",
+        "127.0.0.1:80",
+        "[::1]:8080",
+        "127.1.2.3",
+    ] {
+        assert!(is_loopback(host), "{}", host);
+    }
+    for host in &[
+        "adventofcode.com",
+        "10.0.0.1:80",
+        "[2001:db8::1]",
+        "localhost.evil.com",
+    ] {
+        assert!(!is_loopback(host), "{}", host);
+    }
+}
@@ -0,0 +1,263 @@
+use std::{
+    env,
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use memmap2::Mmap;
+
+use crate::{
+    error::{Error, Result},
+    read_file,
+};
+
+pub mod cache;
+pub mod fetch;
+
+/// Environment variable naming an extra directory to search for inputs.
+pub const INPUTS_VAR: &str = "AOC_INPUTS";
+
+/// The name of the puzzle input used when no other is asked for.
+pub const DEFAULT_NAME: &str = "input";
+
+/// The `src` directory of this crate, which holds each day's own inputs.
+pub fn default_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src")
+}
+
+/// Where to read a day's input from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A named input such as `input`, `example1` or `large`, looked up as
+    /// `<dir>/<day>/<name>.txt` in each of the [`Resolver`]'s directories.
+    Named(String),
+    /// An explicit file.
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source::Named(DEFAULT_NAME.to_string())
+    }
+}
+
+impl Source {
+    /// Interprets a command line value: `-` is stdin, anything that looks like
+    /// a file (it has a directory or an extension) is a path, and any other
+    /// value is the name of an input.
+    pub fn from_arg(arg: &str) -> Self {
+        let path = Path::new(arg);
+        if arg == "-" {
+            Source::Stdin
+        } else if path.extension().is_some() || path.components().count() > 1 {
+            Source::Path(path.to_path_buf())
+        } else {
+            Source::Named(arg.to_string())
+        }
+    }
+
+    /// Whether the source can be shared between several days.
+    pub fn is_named(&self) -> bool {
+        matches!(self, Source::Named(_))
+    }
+}
+
+/// The contents of an input, either read into memory or mapped from its file.
+#[derive(Debug)]
+pub enum Input {
+    Owned(String),
+    Mapped(Mmap),
+}
+
+impl Input {
+    /// Maps the file at `path` into memory. Only files this tool owns and
+    /// never rewrites may be mapped, which are the cache's objects; anything a
+    /// user can edit is read with [`Resolver::open`] instead.
+    ///
+    /// Empty files cannot be mapped, and pipes such as `/dev/stdin` or
+    /// `<(cat input.txt)` claim to be empty until read, so anything but a
+    /// non-empty regular file is read instead.
+    pub fn map<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let io_error = |source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut file = File::open(path).map_err(io_error)?;
+        let metadata = file.metadata().map_err(io_error)?;
+        if !metadata.is_file() || metadata.len() == 0 {
+            let mut input = String::new();
+            file.read_to_string(&mut input).map_err(io_error)?;
+            return Ok(Input::Owned(input));
+        }
+        // Safety: the mapped bytes are checked once as UTF-8 and then borrowed
+        // as a `&str` for the whole run, so anything writing to the file while
+        // it is mapped is undefined behaviour, not just a wrong answer, and
+        // truncating it kills the process with SIGBUS when the lost pages are
+        // read. Callers only map cache objects, which are named by their hash,
+        // written once and never changed afterwards.
+        let map = unsafe { Mmap::map(&file) }.map_err(io_error)?;
+        Ok(Input::Mapped(map))
+    }
+
+    /// The raw contents, which [`DynChallenge::parse_bytes`] checks are UTF-8.
+    ///
+    /// [`DynChallenge::parse_bytes`]: crate::registry::DynChallenge::parse_bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Input::Owned(input) => input.as_bytes(),
+            Input::Mapped(map) => map,
+        }
+    }
+}
+
+/// Finds inputs by searching a list of directories in order.
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    dirs: Vec<PathBuf>,
+}
+
+impl Resolver {
+    pub fn new(dirs: Vec<PathBuf>) -> Self {
+        Resolver { dirs }
+    }
+
+    /// Searches `dir` if given, then `$AOC_INPUTS` if set, and finally the
+    /// `src` directory of this crate, so the default inputs are found wherever
+    /// the binary is run from.
+    pub fn from_env(dir: Option<PathBuf>) -> Self {
+        let dirs = dir
+            .into_iter()
+            .chain(env::var_os(INPUTS_VAR).map(PathBuf::from))
+            .chain(Some(default_dir()))
+            .collect();
+        Resolver::new(dirs)
+    }
+
+    /// The paths searched for the input `name` of `day`, in order.
+    pub fn candidates(&self, day: &str, name: &str) -> Vec<PathBuf> {
+        self.dirs
+            .iter()
+            .map(|dir| dir.join(day).join(format!("{}.txt", name)))
+            .collect()
+    }
+
+    /// The first of the [candidates](Self::candidates) that exists.
+    pub fn find(&self, day: &str, name: &str) -> Option<PathBuf> {
+        self.candidates(day, name).into_iter().find(|p| p.is_file())
+    }
+
+    fn path(&self, day: &str, name: &str) -> Result<PathBuf> {
+        self.find(day, name).ok_or_else(|| Error::MissingInput {
+            name: name.to_string(),
+            searched: self.candidates(day, name),
+        })
+    }
+
+    pub fn read(&self, day: &str, source: &Source) -> Result<String> {
+        match source {
+            Source::Named(name) => read_file(self.path(day, name)?),
+            Source::Path(path) => read_file(path),
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|source| Error::Io {
+                        path: PathBuf::from("<stdin>"),
+                        source,
+                    })?;
+                Ok(input)
+            }
+        }
+    }
+
+    /// Like [`read`](Self::read), but as an [`Input`] for
+    /// [`DynChallenge::parse_bytes`]. These files belong to the user, who may
+    /// edit or replace them at any time, so they are read into memory rather
+    /// than mapped.
+    ///
+    /// [`DynChallenge::parse_bytes`]: crate::registry::DynChallenge::parse_bytes
+    pub fn open(&self, day: &str, source: &Source) -> Result<Input> {
+        self.read(day, source).map(Input::Owned)
+    }
+}
+
+#[test]
+fn test_source_from_arg() {
+    assert_eq!(Source::from_arg("-"), Source::Stdin);
+    assert_eq!(
+        Source::from_arg("example1"),
+        Source::Named("example1".to_string())
+    );
+    assert_eq!(
+        Source::from_arg("example.txt"),
+        Source::Path(PathBuf::from("example.txt"))
+    );
+    assert_eq!(
+        Source::from_arg("inputs/day01"),
+        Source::Path(PathBuf::from("inputs/day01"))
+    );
+}
+
+#[test]
+fn test_resolver() {
+    let root = env::temp_dir().join(format!("aoc-resolver-{}", std::process::id()));
+    let (first, second) = (root.join("first"), root.join("second"));
+    std::fs::create_dir_all(first.join("day01")).unwrap();
+    std::fs::create_dir_all(second.join("day01")).unwrap();
+    std::fs::write(first.join("day01").join("large.txt"), "1\n2").unwrap();
+    std::fs::write(second.join("day01").join("large.txt"), "3\n4").unwrap();
+    std::fs::write(second.join("day01").join("input.txt"), "5\n6").unwrap();
+
+    let resolver = Resolver::new(vec![first.clone(), second.clone()]);
+    let read = |name: &str| resolver.read("day01", &Source::Named(name.to_string()));
+    assert_eq!(read("large").unwrap(), "1\n2");
+    assert_eq!(read("input").unwrap(), "5\n6");
+
+    let input = resolver.open("day01", &Source::Named("large".to_string()));
+    assert!(matches!(input, Ok(Input::Owned(_))));
+    assert_eq!(input.unwrap().as_bytes(), b"1\n2");
+
+    let input = Input::map(first.join("day01").join("large.txt")).unwrap();
+    assert!(matches!(input, Input::Mapped(_)));
+    assert_eq!(input.as_bytes(), b"1\n2");
+    std::fs::write(first.join("day01").join("empty.txt"), "").unwrap();
+    let input = Input::map(first.join("day01").join("empty.txt"));
+    assert!(matches!(input, Ok(Input::Owned(_))));
+
+    match read("example1") {
+        Err(Error::MissingInput { searched, .. }) => assert_eq!(
+            searched,
+            vec![
+                first.join("day01").join("example1.txt"),
+                second.join("day01").join("example1.txt")
+            ]
+        ),
+        other => panic!("expected a missing input, got {:?}", other),
+    }
+
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_map_pipe() {
+    let fifo = env::temp_dir().join(format!("aoc-fifo-{}", std::process::id()));
+    let made = std::process::Command::new("mkfifo")
+        .arg(&fifo)
+        .status()
+        .unwrap();
+    assert!(made.success());
+    let writer = {
+        let fifo = fifo.clone();
+        std::thread::spawn(move || std::fs::write(fifo, "1721\n979\n").unwrap())
+    };
+
+    let input = Input::map(&fifo).unwrap();
+    assert!(matches!(input, Input::Owned(_)));
+    assert_eq!(input.as_bytes(), b"1721\n979\n");
+    writer.join().unwrap();
+    std::fs::remove_file(fifo).unwrap();
+}
@@ -1,37 +1,129 @@
-use std::{env::args, path::Path};
-pub fn read_file<P: AsRef<Path>>(path: P) -> String {
+use std::{env::args, fmt, path::Path, process::exit};
+
+use answer::Answer;
+use config::Overrides;
+use error::{Error, Result};
+use generate::{Generated, Rng};
+
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String> {
     use std::fs::File;
     use std::io::prelude::*;
 
-    let mut file = File::open(path).expect("could not open file");
+    let path = path.as_ref();
+    let io_error = |source| Error::Io { path: path.to_path_buf(), source };
+
+    let mut file = File::open(path).map_err(io_error)?;
     let mut input = String::new();
-    file.read_to_string(&mut input)
-        .expect("could not read file");
-    input
+    file.read_to_string(&mut input).map_err(io_error)?;
+    Ok(input)
+}
+
+/// Writes `contents` to `path`, creating any missing parent directories.
+pub fn write_file<P: AsRef<Path>>(path: P, contents: &str) -> Result<()> {
+    let path = path.as_ref();
+    let io_error = |source| Error::Io { path: path.to_path_buf(), source };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    std::fs::write(path, contents).map_err(io_error)
 }
 
-pub(crate) trait Challenge: Sized {
-    fn name() -> &'static str;
-    fn new<'a>(input: String) -> Self;
-    fn part_one(&self) -> usize;
-    fn part_two(&self) -> usize;
-
-    fn run() {
-        let name = Self::name();
-        let args = args();
-        if args.len() > 1 {
-            if args.filter(|x| x == name).count() == 0 {
-                return;
-            }
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "one"),
+            Part::Two => write!(f, "two"),
         }
-        let input = read_file(Path::new("src").join(Path::new(name).join("input.txt")));
-        let challenge = Self::new(input);
-        println!("\nRunning challenge {}", name);
-        println!("\tAnswer to part one: {}", challenge.part_one());
-        println!("\tAnswer to part two: {}\n", challenge.part_two());
     }
 }
 
+/// A parsed puzzle input, which may borrow from the input text for `'a`.
+pub(crate) trait Challenge<'a> {
+    fn name() -> &'static str
+    where
+        Self: Sized;
+    fn year() -> u16
+    where
+        Self: Sized,
+    {
+        2020
+    }
+    fn day() -> u8
+    where
+        Self: Sized;
+    fn new(input: &'a str) -> Result<Self>
+    where
+        Self: Sized;
+    fn part_one(&self) -> Result<Answer>;
+    fn part_two(&self) -> Result<Answer>;
+
+    /// Generates a random input of about `size` records, for days that can.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<Generated>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Replaces the puzzle's own parameters, such as the target sum, with
+    /// `overrides`. Challenges without parameters reject any.
+    fn configure(&mut self, overrides: &Overrides) -> Result<()> {
+        overrides.none()
+    }
+
+    /// A detailed account of the input, such as what is wrong with each of
+    /// day 4's passports, for days that give one.
+    fn report(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    fn part(&self, part: Part) -> Result<Answer> {
+        match part {
+            Part::One => self.part_one(),
+            Part::Two => self.part_two(),
+        }
+    }
+}
+
+/// Names a [`Challenge`] for inputs of any lifetime, so it can be registered.
+///
+/// Challenges that own their data get this for free. One that borrows from its
+/// input implements it for its `'static` form, e.g. `Solver for Day02<'static>`.
+pub(crate) trait Solver: 'static {
+    type For<'a>: Challenge<'a>;
+}
+
+impl<C: for<'a> Challenge<'a> + 'static> Solver for C {
+    type For<'a> = C;
+}
+
+mod answer;
+mod bench;
+mod check;
+mod cli;
+mod config;
+#[cfg(test)]
+mod differential;
+mod error;
+#[cfg(test)]
+mod examples;
+mod generate;
+mod grid;
+mod input;
+mod parallel;
+mod registry;
+mod report;
 mod parsers;
 mod day01;
 mod day02;
@@ -48,17 +140,5 @@ mod day12;
 mod day13;
 
 fn main() {
-    day01::Day01::run();
-    day02::Day02::run();
-    day03::Day03::run();
-    day04::Day04::run();
-    day05::Day05::run();
-    day06::Day06::run();
-    day07::Day07::run();
-    day08::Day08::run();
-    day09::Day09::run();
-    day10::Day10::run();
-    day11::Day11::run();
-    day12::Day12::run();
-    day13::Day13::run();
+    exit(cli::main(&registry::challenges(), args().skip(1)));
 }
@@ -0,0 +1,82 @@
+use std::{
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+/// The number of jobs to run at once when not told otherwise.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Applies `f` to every item on up to `jobs` threads, handing each result to
+/// `emit` in the order of `items` as soon as it and all those before it are done.
+pub fn for_each_ordered<T, R, F, E>(items: &[T], jobs: usize, f: F, mut emit: E)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    E: FnMut(R),
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        items.iter().map(f).for_each(emit);
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (next, f) = (&next, &f);
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else { break };
+                if sender.send((i, f(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut emitted = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&emitted) {
+                emit(result);
+                emitted += 1;
+            }
+        }
+    });
+}
+
+#[test]
+fn test_for_each_ordered() {
+    use std::time::Duration;
+
+    let items: Vec<u64> = (0..20).collect();
+    for &jobs in &[1, 3, 8, 64] {
+        let mut output = vec![];
+        for_each_ordered(
+            &items,
+            jobs,
+            |&n| {
+                // later items finish first
+                thread::sleep(Duration::from_millis(20 - n));
+                n * n
+            },
+            |n| output.push(n),
+        );
+        assert_eq!(output, items.iter().map(|n| n * n).collect::<Vec<_>>());
+    }
+
+    let mut output: Vec<u8> = vec![];
+    for_each_ordered(&[] as &[u8], 4, |&n| n, |n| output.push(n));
+    assert!(output.is_empty());
+}
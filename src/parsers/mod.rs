@@ -1,8 +1,22 @@
-use std::{fmt::Debug, str::FromStr};
+use std::str::FromStr;
 
-use nom::{IResult, Parser, character::complete::{digit1, line_ending}, combinator::map_res, error::{FromExternalError, ParseError}, multi::{many1, separated_list1}};
+use nom::{
+    branch::alt,
+    character::complete::{char, digit1, line_ending, multispace0, one_of, space0, space1},
+    combinator::{map_res, opt, recognize},
+    error::{ErrorKind, FromExternalError, ParseError, VerboseError},
+    multi::{many1, separated_list1},
+    sequence::{pair, separated_pair, terminated},
+    Parser,
+};
 
-pub fn parse<'a, O, F, E>(f: F) -> impl FnMut(&'a str) -> IResult<&str, O, E>
+use crate::error::{Error, Result};
+
+/// The result of a day's parsers. Errors keep the trail of [`nom::error::context`]s
+/// they passed through, which [`all`] turns into a diagnostic.
+pub type IResult<I, O> = nom::IResult<I, O, VerboseError<I>>;
+
+pub fn parse<'a, O, F, E>(f: F) -> impl FnMut(&'a str) -> nom::IResult<&'a str, O, E>
 where
     O: FromStr,
     F: Parser<&'a str, &'a str, E>,
@@ -11,14 +25,39 @@ where
     map_res(f, str::parse)
 }
 
-pub fn number<'a, O>(input: &'a str) -> IResult<&str, O>
+pub fn number<O>(input: &str) -> IResult<&str, O>
 where
     O: FromStr,
 {
     parse(digit1)(input)
 }
 
-pub fn lines<'a, O, E, F>(f: F) -> impl FnMut(&'a str) -> IResult<&str, Vec<O>, E>
+/// A number with an optional `+` or `-` sign.
+pub fn signed<O>(input: &str) -> IResult<&str, O>
+where
+    O: FromStr,
+{
+    parse(recognize(pair(opt(one_of("+-")), digit1)))(input)
+}
+
+/// One character, looked up in `table`.
+pub fn from_char<'t, T: Clone>(table: &'t [(char, T)]) -> impl Fn(&str) -> IResult<&str, T> + 't {
+    move |input: &str| {
+        let mut chars = input.chars();
+        let found = chars
+            .next()
+            .and_then(|c| table.iter().find(|(key, _)| *key == c));
+        match found {
+            Some((_, value)) => Ok((chars.as_str(), value.clone())),
+            None => Err(nom::Err::Error(VerboseError::from_error_kind(
+                input,
+                ErrorKind::OneOf,
+            ))),
+        }
+    }
+}
+
+pub fn lines<'a, O, E, F>(f: F) -> impl FnMut(&'a str) -> nom::IResult<&'a str, Vec<O>, E>
 where
     F: Parser<&'a str, O, E>,
     E: ParseError<&'a str>,
@@ -26,19 +65,127 @@ where
     separated_list1(line_ending, f)
 }
 
-pub fn all<I, O, E>(result: IResult<I, O, E>) -> O
+pub fn grid<'a, O, E, F>(f: F) -> impl FnMut(&'a str) -> nom::IResult<&'a str, Vec<Vec<O>>, E>
 where
-    E: ParseError<I>,
-    nom::Err<E>: Debug,
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
 {
-    result.unwrap().1
+    lines(many1(f))
 }
 
+/// Groups separated by a blank line, such as the passports of day 4.
+pub fn groups<'a, O, E, F>(f: F) -> impl FnMut(&'a str) -> nom::IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_list1(pair(line_ending, line_ending), f)
+}
 
-pub fn grid<'a, O, E, F>(f: F) -> impl FnMut(&'a str) -> IResult<&str, Vec<Vec<O>>, E>
+/// Items separated by spaces or single line breaks, so a record may span
+/// several lines but ends at a blank one.
+pub fn record<'a, O, E, F>(f: F) -> impl FnMut(&'a str) -> nom::IResult<&'a str, Vec<O>, E>
 where
     F: Parser<&'a str, O, E>,
     E: ParseError<&'a str>,
 {
-    lines(many1(f))
+    separated_list1(alt((space1, line_ending)), f)
+}
+
+/// A `key:value` pair.
+pub fn key_value<'a, K, V, E, F, G>(
+    key: F,
+    value: G,
+) -> impl FnMut(&'a str) -> nom::IResult<&'a str, (K, V), E>
+where
+    F: Parser<&'a str, K, E>,
+    G: Parser<&'a str, V, E>,
+    E: ParseError<&'a str>,
+{
+    separated_pair(key, char(':'), value)
+}
+
+/// Items separated by commas, optionally followed by spaces.
+pub fn comma_list<'a, O, E, F>(f: F) -> impl FnMut(&'a str) -> nom::IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    separated_list1(pair(char(','), space0), f)
+}
+
+/// Runs `parser` over the whole of `input`, reporting failures with their
+/// position in `input`. Only whitespace may follow what `parser` consumes, so
+/// a malformed record is an error rather than the silent end of the input.
+pub fn all<'a, O, F>(parser: F, input: &'a str) -> Result<O>
+where
+    F: Parser<&'a str, O, VerboseError<&'a str>>,
+{
+    let (rest, output) = terminated(parser, multispace0)
+        .parse(input)
+        .map_err(|err| Error::parse(input, err))?;
+    if !rest.is_empty() {
+        return Err(Error::at(input, rest, ErrorKind::Eof, None));
+    }
+    Ok(output)
+}
+
+#[test]
+fn test_numbers() {
+    assert_eq!(number::<u32>("12x"), Ok(("x", 12)));
+    assert!(number::<u32>("-12").is_err());
+    assert_eq!(signed::<i32>("-12x"), Ok(("x", -12)));
+    assert_eq!(signed::<i32>("+12"), Ok(("", 12)));
+    assert!(signed::<u8>("-1").is_err());
+}
+
+#[test]
+fn test_from_char() {
+    let spot = from_char(&[('.', false), ('#', true)]);
+    assert_eq!(spot("#."), Ok((".", true)));
+    assert_eq!(spot("."), Ok(("", false)));
+    assert!(spot("x").is_err());
+    assert!(spot("").is_err());
+}
+
+#[test]
+fn test_groups() {
+    let input = "a:1 b:2\nc:3\n\nd:4";
+    let (rest, output) = groups(record(key_value(
+        nom::character::complete::alpha1,
+        number::<u8>,
+    )))(input)
+    .unwrap();
+    assert_eq!(rest, "");
+    assert_eq!(
+        output,
+        vec![vec![("a", 1), ("b", 2), ("c", 3)], vec![("d", 4)]]
+    );
+
+    let (rest, output) = comma_list(number::<u8>)("1,2, 3\n4").unwrap();
+    assert_eq!(rest, "\n4");
+    assert_eq!(output, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_all() {
+    assert_eq!(all(lines(number::<u8>), "1\n2\n\n").unwrap(), vec![1, 2]);
+    assert_eq!(all(lines(number::<u8>), "1\r\n2\r\n").unwrap(), vec![1, 2]);
+    assert!(matches!(
+        all(lines(number::<u8>), "1\n2x\n3"),
+        Err(Error::Parse {
+            line: 2,
+            column: 2,
+            kind: ErrorKind::Eof,
+            ..
+        })
+    ));
+    assert!(matches!(
+        all(lines(number::<u8>), "300\n1"),
+        Err(Error::Parse {
+            line: 1,
+            column: 1,
+            ..
+        })
+    ));
 }
@@ -0,0 +1,205 @@
+use std::{marker::PhantomData, str};
+
+#[cfg(test)]
+use crate::Part;
+use crate::{
+    config::Overrides,
+    error::{Error, Result},
+    generate::{Generated, Rng},
+    Challenge, Solver,
+};
+
+/// Object safe view over a [`Challenge`] type, so that every solver can be
+/// stored, looked up and run through the same [`Registry`].
+pub trait DynChallenge: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn year(&self) -> u16;
+    fn day(&self) -> u8;
+    /// Parses `input` and applies `overrides` to the parsed challenge.
+    fn parse<'a>(
+        &self,
+        input: &'a str,
+        overrides: &Overrides,
+    ) -> Result<Box<dyn Challenge<'a> + 'a>>;
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated>;
+
+    /// Parses input that has not been checked to be UTF-8, such as a
+    /// memory-mapped file, without copying it.
+    fn parse_bytes<'a>(
+        &self,
+        input: &'a [u8],
+        overrides: &Overrides,
+    ) -> Result<Box<dyn Challenge<'a> + 'a>> {
+        let text = str::from_utf8(input).map_err(|err| Error::utf8(input, err))?;
+        self.parse(text, overrides)
+    }
+}
+
+struct Registered<S>(PhantomData<fn() -> S>);
+
+impl<S: Solver> DynChallenge for Registered<S> {
+    fn name(&self) -> &'static str {
+        <S::For<'static>>::name()
+    }
+    fn year(&self) -> u16 {
+        <S::For<'static>>::year()
+    }
+    fn day(&self) -> u8 {
+        <S::For<'static>>::day()
+    }
+    fn parse<'a>(
+        &self,
+        input: &'a str,
+        overrides: &Overrides,
+    ) -> Result<Box<dyn Challenge<'a> + 'a>> {
+        let mut challenge = <S::For<'a>>::new(input)?;
+        challenge.configure(overrides)?;
+        Ok(Box::new(challenge))
+    }
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<Generated> {
+        <S::For<'static>>::generate(rng, size)
+    }
+}
+
+#[derive(Default)]
+pub struct Registry {
+    challenges: Vec<Box<dyn DynChallenge>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `S` to the registry, keeping challenges ordered by year then day.
+    pub fn register<S: Solver>(mut self) -> Self {
+        let challenge: Box<dyn DynChallenge> = Box::new(Registered::<S>(PhantomData));
+        let key = (challenge.year(), challenge.day());
+        let index = self
+            .challenges
+            .partition_point(|c| (c.year(), c.day()) <= key);
+        self.challenges.insert(index, challenge);
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn DynChallenge> {
+        self.challenges.iter().map(|c| c.as_ref())
+    }
+
+    /// Looks up a challenge by its name (`day07`) or day number (`7`).
+    pub fn get(&self, query: &str) -> Option<&dyn DynChallenge> {
+        let number = query.trim_start_matches("day").parse::<u8>().ok();
+        self.iter()
+            .find(|c| c.name() == query || Some(c.day()) == number)
+    }
+}
+
+pub fn challenges() -> Registry {
+    use crate::*;
+    Registry::new()
+        .register::<day01::Day01>()
+        .register::<day02::Day02<'static>>()
+        .register::<day03::Day03>()
+        .register::<day04::Day04<'static>>()
+        .register::<day05::Day05>()
+        .register::<day06::Day06>()
+        .register::<day07::Day07<'static>>()
+        .register::<day08::Day08>()
+        .register::<day09::Day09>()
+        .register::<day10::Day10>()
+        .register::<day11::Day11>()
+        .register::<day12::Day12>()
+        .register::<day13::Day13>()
+}
+
+#[test]
+fn test_registry_order() {
+    use crate::{day01::Day01, day07::Day07, day13::Day13};
+    let registry = Registry::new()
+        .register::<Day13>()
+        .register::<Day01>()
+        .register::<Day07<'static>>();
+
+    let days: Vec<u8> = registry.iter().map(|c| c.day()).collect();
+    assert_eq!(days, vec![1, 7, 13]);
+}
+
+#[test]
+fn test_registry_get() {
+    let registry = challenges();
+    assert_eq!(registry.iter().count(), 13);
+    assert_eq!(registry.get("day07").map(|c| c.day()), Some(7));
+    assert_eq!(registry.get("7").map(|c| c.name()), Some("day07"));
+    assert_eq!(registry.get("07").map(|c| c.name()), Some("day07"));
+    assert!(registry.get("day25").is_none());
+    assert!(registry.iter().all(|c| c.year() == 2020));
+}
+
+#[test]
+fn test_solve_errors() {
+    let registry = challenges();
+    let day01 = registry.get("day01").unwrap();
+    assert!(matches!(
+        day01.parse("abc", &Overrides::default()).err(),
+        Some(Error::Parse {
+            line: 1,
+            column: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        day01.parse_bytes(b"1\n\xff", &Overrides::default()).err(),
+        Some(Error::Parse {
+            line: 2,
+            column: 1,
+            ..
+        })
+    ));
+    assert!(matches!(
+        day01
+            .parse("1\n2\n3", &Overrides::default())
+            .unwrap()
+            .part(Part::One),
+        Err(Error::NoSolution(_))
+    ));
+}
+
+#[test]
+fn test_solve() {
+    let registry = challenges();
+    let day01 = registry.get("day01").unwrap();
+    let input = "1721\n979\n366\n299\n675\n1456";
+    assert_eq!(
+        day01
+            .parse(input, &Overrides::default())
+            .unwrap()
+            .part(Part::Two)
+            .unwrap(),
+        241861950
+    );
+
+    let mut overrides = Overrides::default();
+    overrides.set("target", "1345");
+    let day01 = day01.parse(input, &overrides).unwrap();
+    assert_eq!(day01.part(Part::One).unwrap(), 979 * 366);
+    assert!(day01.part(Part::Two).is_err());
+
+    overrides.set("trget", "2020");
+    assert!(matches!(
+        registry
+            .get("day01")
+            .unwrap()
+            .parse(input, &overrides)
+            .err(),
+        Some(Error::Params(_))
+    ));
+    // days without parameters reject them all
+    assert!(matches!(
+        registry
+            .get("day02")
+            .unwrap()
+            .parse("1-3 a: abcde", &overrides)
+            .err(),
+        Some(Error::Params(_))
+    ));
+}
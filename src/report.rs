@@ -0,0 +1,557 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::{answer::Answer, bench::format_duration, check, error::Result, Part};
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// The part ran and nothing was expected of it.
+    Ok,
+    Pass,
+    Fail,
+    /// The part ran but there was no expected answer to compare with.
+    Missing,
+    /// The part was not run because there was no input.
+    Skip,
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match self {
+            Status::Ok => "ok",
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Missing => "missing",
+            Status::Skip => "skip",
+            Status::Error => "error",
+        };
+        f.write_str(status)
+    }
+}
+
+/// The outcome of one part of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub day: &'static str,
+    pub part: Part,
+    pub status: Status,
+    pub answer: Option<Answer>,
+    pub expected: Option<Answer>,
+    pub error: Option<String>,
+    /// `None` when the input was never parsed.
+    pub parse: Option<Duration>,
+    /// `None` when the part was never run.
+    pub elapsed: Option<Duration>,
+}
+
+impl Record {
+    /// A record for a part that could not run because its day failed as a whole.
+    pub fn failed(day: &'static str, part: Part, status: Status, error: String) -> Self {
+        Record {
+            day,
+            part,
+            status,
+            answer: None,
+            expected: None,
+            error: Some(error),
+            parse: None,
+            elapsed: None,
+        }
+    }
+
+    /// A record for a part of `run`, which has nothing to compare against.
+    pub fn solved(
+        day: &'static str,
+        part: Part,
+        answer: Result<Answer>,
+        parse: Duration,
+        elapsed: Duration,
+    ) -> Self {
+        let (status, answer, error) = match answer {
+            Ok(answer) => (Status::Ok, Some(answer), None),
+            Err(err) => (Status::Error, None, Some(err.to_string())),
+        };
+        Record {
+            day,
+            part,
+            status,
+            answer,
+            expected: None,
+            error,
+            parse: Some(parse),
+            elapsed: Some(elapsed),
+        }
+    }
+
+    pub fn from_check(day: &'static str, report: check::Report) -> Vec<Self> {
+        let parse = report.parse;
+        let parts = match report.parts {
+            Ok(parts) => parts,
+            Err(err) => {
+                return Part::ALL
+                    .iter()
+                    .map(|&part| Record::failed(day, part, Status::Error, err.to_string()))
+                    .collect()
+            }
+        };
+        parts
+            .into_iter()
+            .map(|part| {
+                let (status, answer, expected, error) = match part.status {
+                    check::Status::Pass => (Status::Pass, None, None, None),
+                    check::Status::Fail { expected, actual } => {
+                        (Status::Fail, Some(actual), Some(expected), None)
+                    }
+                    check::Status::Missing(actual) => (Status::Missing, Some(actual), None, None),
+                    check::Status::Error(err) => (Status::Error, None, None, Some(err.to_string())),
+                };
+                Record {
+                    day,
+                    part: part.part,
+                    status,
+                    // a passing answer is its expected value
+                    answer: answer.or_else(|| expected.clone()),
+                    expected,
+                    error,
+                    parse: Some(parse),
+                    elapsed: Some(part.elapsed),
+                }
+            })
+            .collect()
+    }
+
+    fn answer(&self) -> String {
+        match &self.answer {
+            Some(Answer::Lines(lines)) => lines.join("/"),
+            Some(answer) => answer.to_string(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Writes records as they arrive, one day at a time.
+pub trait Formatter {
+    /// Called once per day, in day order, with a record for each part.
+    fn day(&mut self, records: &[Record]) -> io::Result<()>;
+
+    /// Called after the last day.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Tap,
+    Table,
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "tap" => Ok(Format::Tap),
+            "table" => Ok(Format::Table),
+            _ => Err(format!(
+                "invalid format '{}', expected text, json, csv, tap or table",
+                s
+            )),
+        }
+    }
+}
+
+impl Format {
+    pub fn formatter<'a, W: Write + 'a>(self, out: W) -> Box<dyn Formatter + 'a> {
+        match self {
+            Format::Text => Box::new(Text(out)),
+            Format::Json => Box::new(JsonLines(out)),
+            Format::Csv => Box::new(Csv::new(out)),
+            Format::Tap => Box::new(Tap::new(out)),
+            Format::Table => Box::new(Table::new(out)),
+        }
+    }
+}
+
+/// The human readable output of `run`. Errors go to stderr.
+pub struct Text<W>(pub W);
+
+impl<W: Write> Formatter for Text<W> {
+    fn day(&mut self, records: &[Record]) -> io::Result<()> {
+        let first = match records.first() {
+            Some(first) => first,
+            None => return Ok(()),
+        };
+        let parse = match first.parse {
+            Some(parse) => parse,
+            None => {
+                let error = first.error.as_deref().unwrap_or_default();
+                eprintln!("error: {}: {}", first.day, error);
+                return Ok(());
+            }
+        };
+
+        writeln!(
+            self.0,
+            "\nRunning challenge {} (parsed in {})",
+            first.day,
+            format_duration(parse)
+        )?;
+        for record in records {
+            let elapsed = record.elapsed.map(format_duration).unwrap_or_default();
+            match (&record.answer, &record.error) {
+                (Some(Answer::Lines(lines)), _) => {
+                    writeln!(self.0, "\tAnswer to part {} ({}):", record.part, elapsed)?;
+                    for line in lines {
+                        writeln!(self.0, "\t\t{}", line)?;
+                    }
+                }
+                (Some(answer), _) => writeln!(
+                    self.0,
+                    "\tAnswer to part {}: {} ({})",
+                    record.part, answer, elapsed
+                )?,
+                (None, error) => eprintln!(
+                    "\tPart {} failed: {}",
+                    record.part,
+                    error.as_deref().unwrap_or_default()
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct Json<'a> {
+    day: &'a str,
+    part: String,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answer: Option<&'a Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected: Option<&'a Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_ns: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_ns: Option<u128>,
+}
+
+impl<'a> From<&'a Record> for Json<'a> {
+    fn from(record: &'a Record) -> Self {
+        Json {
+            day: record.day,
+            part: record.part.to_string(),
+            status: record.status,
+            answer: record.answer.as_ref(),
+            expected: record.expected.as_ref(),
+            error: record.error.as_ref(),
+            parse_ns: record.parse.map(|d| d.as_nanos()),
+            elapsed_ns: record.elapsed.map(|d| d.as_nanos()),
+        }
+    }
+}
+
+/// One JSON object per line and per record.
+pub struct JsonLines<W>(pub W);
+
+impl<W: Write> Formatter for JsonLines<W> {
+    fn day(&mut self, records: &[Record]) -> io::Result<()> {
+        for record in records {
+            serde_json::to_writer(&mut self.0, &Json::from(record))?;
+            writeln!(self.0)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Csv<W> {
+    out: W,
+    header: bool,
+}
+
+impl<W> Csv<W> {
+    pub fn new(out: W) -> Self {
+        Csv { out, header: false }
+    }
+}
+
+/// Quotes a field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl<W: Write> Formatter for Csv<W> {
+    fn day(&mut self, records: &[Record]) -> io::Result<()> {
+        if !self.header {
+            writeln!(
+                self.out,
+                "day,part,status,answer,expected,error,parse_ns,elapsed_ns"
+            )?;
+            self.header = true;
+        }
+        let to_string = |value: Option<&dyn fmt::Display>| match value {
+            Some(value) => csv_field(&value.to_string()),
+            None => String::new(),
+        };
+        for r in records {
+            writeln!(
+                self.out,
+                "{},{},{},{},{},{},{},{}",
+                r.day,
+                r.part,
+                r.status,
+                to_string(r.answer.as_ref().map(|a| a as _)),
+                to_string(r.expected.as_ref().map(|a| a as _)),
+                to_string(r.error.as_ref().map(|e| e as _)),
+                to_string(r.parse.map(|d| d.as_nanos()).as_ref().map(|n| n as _)),
+                to_string(r.elapsed.map(|d| d.as_nanos()).as_ref().map(|n| n as _)),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The Test Anything Protocol, with the plan at the end.
+pub struct Tap<W> {
+    out: W,
+    count: usize,
+}
+
+impl<W: Write> Tap<W> {
+    pub fn new(out: W) -> Self {
+        Tap { out, count: 0 }
+    }
+}
+
+impl<W: Write> Formatter for Tap<W> {
+    fn day(&mut self, records: &[Record]) -> io::Result<()> {
+        if self.count == 0 {
+            writeln!(self.out, "TAP version 13")?;
+        }
+        for r in records {
+            self.count += 1;
+            let ok = match r.status {
+                Status::Fail | Status::Error => "not ok",
+                _ => "ok",
+            };
+            write!(
+                self.out,
+                "{} {} - {} part {}",
+                ok, self.count, r.day, r.part
+            )?;
+            match r.status {
+                Status::Skip => writeln!(self.out, " # SKIP no input")?,
+                Status::Missing => writeln!(self.out, " # SKIP no expected answer")?,
+                _ => writeln!(self.out)?,
+            }
+
+            // a YAML block describing the failure; JSON strings are valid YAML
+            let quote = |s: String| serde_json::to_string(&s).unwrap();
+            if let (Status::Error, Some(error)) = (r.status, &r.error) {
+                writeln!(
+                    self.out,
+                    "  ---\n  message: {}\n  ...",
+                    quote(error.clone())
+                )?;
+            } else if let (Status::Fail, Some(expected), Some(actual)) =
+                (r.status, &r.expected, &r.answer)
+            {
+                writeln!(
+                    self.out,
+                    "  ---\n  expected: {}\n  actual: {}\n  ...",
+                    quote(expected.to_string()),
+                    quote(actual.to_string())
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.count == 0 {
+            writeln!(self.out, "TAP version 13")?;
+        }
+        writeln!(self.out, "1..{}", self.count)
+    }
+}
+
+/// An aligned table, written once every day is done.
+pub struct Table<W> {
+    out: W,
+    rows: Vec<[String; 5]>,
+}
+
+impl<W: Write> Table<W> {
+    pub fn new(out: W) -> Self {
+        let header = ["day", "part", "status", "answer", "time"];
+        Table {
+            out,
+            rows: vec![header.map(String::from)],
+        }
+    }
+}
+
+impl<W: Write> Formatter for Table<W> {
+    fn day(&mut self, records: &[Record]) -> io::Result<()> {
+        for r in records {
+            let answer = match &r.error {
+                Some(error) => error.lines().next().unwrap_or_default().to_string(),
+                None => r.answer(),
+            };
+            self.rows.push([
+                r.day.to_string(),
+                r.part.to_string(),
+                r.status.to_string(),
+                answer,
+                r.elapsed.map(format_duration).unwrap_or_default(),
+            ]);
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let mut widths = [0; 5];
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        for row in &self.rows {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, &width))| match i {
+                    // right align the timings
+                    4 => format!("{:>1$}", cell, width),
+                    _ => format!("{:<1$}", cell, width),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(self.out, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn records() -> Vec<Record> {
+    let ms = Duration::from_millis;
+    vec![
+        Record {
+            day: "day01",
+            part: Part::One,
+            status: Status::Pass,
+            answer: Some(514579usize.into()),
+            expected: None,
+            error: None,
+            parse: Some(ms(1)),
+            elapsed: Some(ms(2)),
+        },
+        Record {
+            day: "day01",
+            part: Part::Two,
+            status: Status::Fail,
+            answer: Some("a,\"b\"".into()),
+            expected: Some(3usize.into()),
+            error: None,
+            parse: Some(ms(1)),
+            elapsed: Some(ms(3)),
+        },
+        Record::failed("day02", Part::One, Status::Error, "bad input".to_string()),
+    ]
+}
+
+#[cfg(test)]
+fn render(format: Format) -> String {
+    let records = records();
+    let mut out = vec![];
+    let mut formatter = format.formatter(&mut out);
+    formatter.day(&records[..2]).unwrap();
+    formatter.day(&records[2..]).unwrap();
+    formatter.finish().unwrap();
+    drop(formatter);
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_json_lines() {
+    let output = render(Format::Json);
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["day"], "day01");
+    assert_eq!(lines[0]["part"], "one");
+    assert_eq!(lines[0]["status"], "pass");
+    assert_eq!(lines[0]["answer"], 514579);
+    assert_eq!(lines[0]["elapsed_ns"], 2_000_000);
+    assert_eq!(lines[1]["expected"], 3);
+    assert_eq!(lines[2]["error"], "bad input");
+    assert!(lines[2].get("parse_ns").is_none());
+}
+
+#[test]
+fn test_csv() {
+    assert_eq!(
+        render(Format::Csv),
+        "day,part,status,answer,expected,error,parse_ns,elapsed_ns
+day01,one,pass,514579,,,1000000,2000000
+day01,two,fail,\"a,\"\"b\"\"\",3,,1000000,3000000
+day02,one,error,,,bad input,,
+"
+    );
+}
+
+#[test]
+fn test_tap() {
+    assert_eq!(
+        render(Format::Tap),
+        "TAP version 13
+ok 1 - day01 part one
+not ok 2 - day01 part two
+  ---
+  expected: \"3\"
+  actual: \"a,\\\"b\\\"\"
+  ...
+not ok 3 - day02 part one
+  ---
+  message: \"bad input\"
+  ...
+1..3
+"
+    );
+}
+
+#[test]
+fn test_table() {
+    assert_eq!(
+        render(Format::Table),
+        "day    part  status  answer       time
+day01  one   pass    514579     2.00ms
+day01  two   fail    a,\"b\"      3.00ms
+day02  one   error   bad input
+"
+    );
+}